@@ -24,7 +24,9 @@ extern_lib liblean_hashbrown pkg := do
   let setFile ← inputFile <| pkg.dir / "src" / "set.rs"
   let mapFile ← inputFile <| pkg.dir / "src" / "map.rs"
   let ffiFile ← inputFile <| pkg.dir / "src" / "ffi.rs"
-  buildFileAfterDepArray libFile #[cargoFile, librsFile, setFile, mapFile, ffiFile] (fun _ => proc {
+  let randomFile ← inputFile <| pkg.dir / "src" / "random.rs"
+  let joinFile ← inputFile <| pkg.dir / "src" / "join.rs"
+  buildFileAfterDepArray libFile #[cargoFile, librsFile, setFile, mapFile, ffiFile, randomFile, joinFile] (fun _ => proc {
     cmd := "cargo",
     args := #["build", "--release", "-Zunstable-options", "--target-dir", (pkg.buildDir / "rust").toString, "--out-dir", (pkg.buildDir / "lib").toString]
   } true) (pure BuildTrace.nil)
@@ -1,3 +1,4 @@
 import HashBrown.HashMap
 import HashBrown.HashSet
-import HashBrown.HashState
\ No newline at end of file
+import HashBrown.HashState
+import HashBrown.Join
\ No newline at end of file
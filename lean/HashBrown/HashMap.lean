@@ -11,9 +11,34 @@ opaque HashMapIterPointed : (κ ν : Type)  → NonemptyType
 def HashMapIter (κ ν : Type) : Type := (HashMapIterPointed κ ν).type
 instance : Nonempty (HashMapIter κ ν) := (HashMapIterPointed κ ν).property
 
+-- Opaque type for HashMapKeysIter
+opaque HashMapKeysIterPointed : (κ ν : Type)  → NonemptyType
+def HashMapKeysIter (κ ν : Type) : Type := (HashMapKeysIterPointed κ ν).type
+instance : Nonempty (HashMapKeysIter κ ν) := (HashMapKeysIterPointed κ ν).property
+
+-- Opaque type for HashMapValuesIter
+opaque HashMapValuesIterPointed : (κ ν : Type)  → NonemptyType
+def HashMapValuesIter (κ ν : Type) : Type := (HashMapValuesIterPointed κ ν).type
+instance : Nonempty (HashMapValuesIter κ ν) := (HashMapValuesIterPointed κ ν).property
+
 @[extern "lean_hashbrown_hashmap_create"]
 opaque HashMap.mk : {κ ν : Type} → HashMap κ ν
 
+@[extern "lean_hashbrown_hashmap_with_capacity"]
+opaque HashMap.withCapacity : {κ ν : Type} → USize → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_capacity"]
+opaque HashMap.capacity : {κ ν : Type} → @& HashMap κ ν → USize
+
+@[extern "lean_hashbrown_hashmap_reserve"]
+opaque HashMap.reserve : {κ ν : Type} → HashMap κ ν → USize → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_shrink_to"]
+opaque HashMap.shrinkTo : {κ ν : Type} → HashMap κ ν → USize → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_clear"]
+opaque HashMap.clear : {κ ν : Type} → HashMap κ ν → HashMap κ ν
+
 @[extern "lean_hashbrown_hashmap_insert"]
 private opaque HashMap.insertRaw : {κ ν : Type} 
   → HashMap κ ν → UInt64 → κ → ν → @&(κ → Bool) → HashMap κ ν 
@@ -26,16 +51,160 @@ private opaque HashMap.containsRaw : {κ ν : Type}
 private opaque HashMap.getValueRaw? : {κ ν : Type}
   → @& HashMap κ ν → UInt64 → @&(κ → Bool) → Option ν
 
+@[extern "lean_hashbrown_hashmap_get_key"]
+private opaque HashMap.getKeyRaw? : {κ ν : Type}
+  → @& HashMap κ ν → UInt64 → @&(κ → Bool) → Option κ
+
+@[extern "lean_hashbrown_hashmap_get_entry"]
+private opaque HashMap.getEntryRaw? : {κ ν : Type}
+  → @& HashMap κ ν → UInt64 → @&(κ → Bool) → Option (κ × ν)
+
 @[extern "lean_hashbrown_hashmap_remove"]
 private opaque HashMap.removeRaw : {κ ν : Type} 
   → HashMap κ ν → UInt64 → @&(κ → Bool) → HashMap κ ν
 
+@[extern "lean_hashbrown_hashmap_insert_many"]
+private opaque HashMap.insertManyRaw : {κ ν : Type} 
+  → HashMap κ ν → @& Array (κ × ν) → @&(κ → UInt64) → @&(κ → κ → Bool) → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_insert_replace"]
+private opaque HashMap.insertRaw' : {κ ν : Type} 
+  → HashMap κ ν → UInt64 → κ → ν → @&(κ → Bool) → HashMap κ ν × Option ν
+
+@[extern "lean_hashbrown_hashmap_insert_if_new"]
+private opaque HashMap.insertIfNewRaw : {κ ν : Type} 
+  → HashMap κ ν → UInt64 → κ → ν → @&(κ → Bool) → HashMap κ ν × Bool
+
+@[extern "lean_hashbrown_hashmap_erase"]
+private opaque HashMap.eraseRaw? : {κ ν : Type} 
+  → HashMap κ ν → UInt64 → @&(κ → Bool) → HashMap κ ν × Option (κ × ν)
+
+@[extern "lean_hashbrown_hashmap_alter"]
+private opaque HashMap.alterRaw : {κ ν : Type}
+  → HashMap κ ν → UInt64 → κ → @&(κ → Bool) → @&(Option ν → Option ν) → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_modify"]
+private opaque HashMap.modifyRaw : {κ ν : Type}
+  → HashMap κ ν → UInt64 → @&(κ → Bool) → @&(ν → ν) → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_union"]
+private opaque HashMap.unionRaw : {κ ν : Type} 
+  → HashMap κ ν → HashMap κ ν → @&(κ → κ → Bool) → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_union_with"]
+private opaque HashMap.unionWithRaw : {κ ν : Type} 
+  → HashMap κ ν → HashMap κ ν → @&(κ → κ → Bool) → @&(κ → ν → ν → ν) → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_merge_with"]
+private opaque HashMap.mergeWithRaw : {κ ν : Type} 
+  → HashMap κ ν → @& HashMap κ ν → @&(κ → κ → Bool) 
+  → @&(κ → ν → ν → Option ν) → @&(κ → ν → Option ν) → @&(κ → ν → Option ν) → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_digest"]
+private opaque HashMap.digest : {κ ν : Type} → @& HashMap κ ν → UInt64
+
+@[extern "lean_hashbrown_hashmap_hash_with"]
+private opaque HashMap.hashWithRaw : {κ ν : Type} → @& HashMap κ ν → @&(ν → UInt64) → UInt64
+
+@[extern "lean_hashbrown_hashmap_eq"]
+private opaque HashMap.eqRaw : {κ ν : Type} 
+  → @& HashMap κ ν → @& HashMap κ ν → @&(κ → κ → Bool) → @&(ν → ν → Bool) → Bool
+
+@[extern "lean_hashbrown_hashmap_is_submap_by"]
+private opaque HashMap.isSubmapByRaw : {κ ν : Type} 
+  → @& HashMap κ ν → @& HashMap κ ν → @&(κ → κ → Bool) → @&(ν → ν → Bool) → Bool
+
+@[extern "lean_hashbrown_hashmap_diff"]
+private opaque HashMap.diffRaw : {κ ν : Type} 
+  → @& HashMap κ ν → @& HashMap κ ν → @&(κ → κ → Bool) → @&(ν → ν → Bool) 
+  → HashMap κ ν × HashMap κ ν × HashMap κ (ν × ν)
+
+@[extern "lean_hashbrown_hashmap_apply_patch"]
+private opaque HashMap.applyPatchRaw : {κ ν : Type} 
+  → HashMap κ ν → @& HashMap κ ν → @& HashMap κ ν → @& HashMap κ (ν × ν) → @&(κ → κ → Bool) 
+  → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_invert"]
+private opaque HashMap.invertRaw : {κ ν : Type} 
+  → @& HashMap κ ν → @&(ν → UInt64) → @&(ν → ν → Bool) → HashMap ν (Array κ)
+
+@[extern "lean_hashbrown_hashmap_compose"]
+private opaque HashMap.composeRaw : {α β γ : Type} 
+  → @& HashMap α β → @& HashMap β γ → @&(β → UInt64) → @&(β → β → Bool) → HashMap α γ
+
+@[extern "lean_hashbrown_hashmap_zip_with"]
+private opaque HashMap.zipWithRaw : {κ ν μ ρ : Type} 
+  → @& HashMap κ ν → @& HashMap κ μ → @&(κ → κ → Bool) 
+  → @&(κ → Option ν → Option μ → Option ρ) → HashMap κ ρ
+
+@[extern "lean_hashbrown_hashmap_group_by"]
+private opaque HashMap.groupByRaw : {α κ : Type} 
+  → @& Array α → @&(α → κ) → @&(κ → UInt64) → @&(κ → κ → Bool) → HashMap κ (Array α)
+
+@[extern "lean_hashbrown_hashmap_count_by"]
+private opaque HashMap.countByRaw : {α κ : Type} 
+  → @& Array α → @&(α → κ) → @&(κ → UInt64) → @&(κ → κ → Bool) → HashMap κ Nat
+
 @[extern "lean_hashbrown_hashmap_len"]
 opaque HashMap.len : {κ ν: Type} → @& HashMap κ ν → USize    
 
+@[extern "lean_hashbrown_hashmap_to_array"]
+opaque HashMap.toArray : {κ ν : Type} → @& HashMap κ ν → Array (κ × ν)
+
+@[extern "lean_hashbrown_hashmap_keys"]
+opaque HashMap.keys : {κ ν : Type} → @& HashMap κ ν → Array κ
+
+@[extern "lean_hashbrown_hashmap_values"]
+opaque HashMap.values : {κ ν : Type} → @& HashMap κ ν → Array ν
+
+@[extern "lean_hashbrown_hashmap_fold"]
+opaque HashMap.fold : {κ ν β : Type} → @&(β → κ → ν → β) → β → @& HashMap κ ν → β
+
+@[extern "lean_hashbrown_hashmap_pop"]
+opaque HashMap.pop? : {κ ν : Type} → HashMap κ ν → HashMap κ ν × Option (κ × ν)
+
+@[extern "lean_hashbrown_hashmap_sample"]
+opaque HashMap.sample : {κ ν : Type} → @& HashMap κ ν → (seed : UInt64) → Option (κ × ν)
+
+@[extern "lean_hashbrown_hashmap_sample_n"]
+opaque HashMap.sampleN : {κ ν : Type} → @& HashMap κ ν → USize → (seed : UInt64) → Array (κ × ν)
+
+@[extern "lean_hashbrown_hashmap_any"]
+opaque HashMap.any : {κ ν : Type} → @& HashMap κ ν → @&(κ → ν → Bool) → Bool
+
+@[extern "lean_hashbrown_hashmap_all"]
+opaque HashMap.all : {κ ν : Type} → @& HashMap κ ν → @&(κ → ν → Bool) → Bool
+
+@[extern "lean_hashbrown_hashmap_count"]
+opaque HashMap.count : {κ ν : Type} → @& HashMap κ ν → @&(κ → ν → Bool) → USize
+
+@[extern "lean_hashbrown_hashmap_find"]
+opaque HashMap.find? : {κ ν : Type} → @& HashMap κ ν → @&(κ → ν → Bool) → Option (κ × ν)
+
+@[extern "lean_hashbrown_hashmap_filter"]
+opaque HashMap.filter : {κ ν : Type} → HashMap κ ν → @&(κ → ν → Bool) → HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_filter_map"]
+opaque HashMap.filterMap : {κ ν μ : Type} → HashMap κ ν → @&(κ → ν → Option μ) → HashMap κ μ
+
+@[extern "lean_hashbrown_hashmap_partition"]
+opaque HashMap.partition : {κ ν : Type} → HashMap κ ν → @&(κ → ν → Bool) → HashMap κ ν × HashMap κ ν
+
+@[extern "lean_hashbrown_hashmap_extract_if"]
+opaque HashMap.extractIf : {κ ν : Type} → HashMap κ ν → @&(κ → ν → Bool) → HashMap κ ν × Array (κ × ν)
+
+@[extern "lean_hashbrown_hashmap_map_values"]
+opaque HashMap.mapValuesWithKey : {κ ν μ : Type} → HashMap κ ν → @&(κ → ν → μ) → HashMap κ μ
+
 @[extern "lean_hashbrown_hashmap_get_iter"]
 opaque HashMap.iter : {κ ν : Type} → HashMap κ ν → HashMapIter κ ν 
 
+@[extern "lean_hashbrown_hashmap_get_keys_iter"]
+opaque HashMap.keysIter : {κ ν : Type} → HashMap κ ν → HashMapKeysIter κ ν
+
+@[extern "lean_hashbrown_hashmap_get_values_iter"]
+opaque HashMap.valuesIter : {κ ν : Type} → HashMap κ ν → HashMapValuesIter κ ν
+
 @[extern "lean_hashbrown_hashmap_iter_has_kv"]
 opaque HashMapIter.hasKV : {κ ν : Type} → @& HashMapIter κ ν → Bool
 
@@ -48,11 +217,44 @@ opaque HashMapIter.getValue? : {κ ν : Type} → @& HashMapIter κ ν → Optio
 @[extern "lean_hashbrown_hashmap_iter_move_next"]
 opaque HashMapIter.next :  {κ ν : Type} → HashMapIter κ ν → HashMapIter κ ν
 
+@[extern "lean_hashbrown_hashmap_iter_next_entry"]
+opaque HashMapIter.next? : {κ ν : Type} → HashMapIter κ ν → Option (κ × ν × HashMapIter κ ν)
+
+@[extern "lean_hashbrown_hashmap_keys_iter_next"]
+opaque HashMapKeysIter.next? : {κ ν : Type} → HashMapKeysIter κ ν → Option (κ × HashMapKeysIter κ ν)
+
+@[extern "lean_hashbrown_hashmap_values_iter_next"]
+opaque HashMapValuesIter.next? : {κ ν : Type} → HashMapValuesIter κ ν → Option (ν × HashMapValuesIter κ ν)
+
 def HashMap.insert {κ ν : Type} [Hashable κ] [BEq κ] (s: HashMap κ ν) (k: κ) (v : ν) : HashMap κ ν :=
   let hash := Hashable.hash k
   let eq := fun (k': κ) => k == k'
   HashMap.insertRaw s hash k v eq
 
+def HashMap.insertMany {κ ν : Type} [Hashable κ] [BEq κ] (s: HashMap κ ν) (xs: @& Array (κ × ν)) : HashMap κ ν :=
+  HashMap.insertManyRaw s xs Hashable.hash (· == ·)
+
+def HashMap.ofArray {κ ν : Type} [Hashable κ] [BEq κ] (xs: @& Array (κ × ν)) : HashMap κ ν :=
+  HashMap.insertMany HashMap.mk xs
+
+def HashMap.ofList {κ ν : Type} [Hashable κ] [BEq κ] (xs: List (κ × ν)) : HashMap κ ν :=
+  HashMap.ofArray xs.toArray
+
+def HashMap.insert' {κ ν : Type} [Hashable κ] [BEq κ] (s: HashMap κ ν) (k: κ) (v : ν) : HashMap κ ν × Option ν :=
+  let hash := Hashable.hash k
+  let eq := fun (k': κ) => k == k'
+  HashMap.insertRaw' s hash k v eq
+
+def HashMap.insertIfNew {κ ν : Type} [Hashable κ] [BEq κ] (s: HashMap κ ν) (k: κ) (v : ν) : HashMap κ ν × Bool :=
+  let hash := Hashable.hash k
+  let eq := fun (k': κ) => k == k'
+  HashMap.insertIfNewRaw s hash k v eq
+
+def HashMap.erase? {κ ν : Type} [Hashable κ] [BEq κ] (s: HashMap κ ν) (k: κ) : HashMap κ ν × Option (κ × ν) :=
+  let hash := Hashable.hash k
+  let eq := fun (k': κ) => k == k'
+  HashMap.eraseRaw? s hash eq
+
 def HashMap.remove {κ ν : Type} [Hashable κ] [BEq κ] (s: HashMap κ ν) (k: κ) : HashMap κ ν :=
   let hash := Hashable.hash k
   let eq := fun (k': κ) => k == k'
@@ -68,6 +270,139 @@ def HashMap.getValue? {κ ν : Type} [Hashable κ] [BEq κ] (s: @& HashMap κ ν
   let eq := fun (k': κ) => k == k'
   HashMap.getValueRaw? s hash eq
 
+def HashMap.getKey? {κ ν : Type} [Hashable κ] [BEq κ] (s: @& HashMap κ ν) (k: κ) : Option κ :=
+  let hash := Hashable.hash k
+  let eq := fun (k': κ) => k == k'
+  HashMap.getKeyRaw? s hash eq
+
+def HashMap.getEntry? {κ ν : Type} [Hashable κ] [BEq κ] (s: @& HashMap κ ν) (k: κ) : Option (κ × ν) :=
+  let hash := Hashable.hash k
+  let eq := fun (k': κ) => k == k'
+  HashMap.getEntryRaw? s hash eq
+
+def HashMap.alter {κ ν : Type} [Hashable κ] [BEq κ] (s: HashMap κ ν) (k: κ) (f: Option ν → Option ν) : HashMap κ ν :=
+  let hash := Hashable.hash k
+  let eq := fun (k': κ) => k == k'
+  HashMap.alterRaw s hash k eq f
+
+def HashMap.modify {κ ν : Type} [Hashable κ] [BEq κ] (s: HashMap κ ν) (k: κ) (f: ν → ν) : HashMap κ ν :=
+  let hash := Hashable.hash k
+  let eq := fun (k': κ) => k == k'
+  HashMap.modifyRaw s hash eq f
+
+def HashMap.shrinkToFit {κ ν : Type} (s: HashMap κ ν) : HashMap κ ν :=
+  HashMap.shrinkTo s 0
+
+def HashMap.mapValues {κ ν μ : Type} (s: HashMap κ ν) (f: ν → μ) : HashMap κ μ :=
+  HashMap.mapValuesWithKey s (fun _ v => f v)
+
+-- keeps the entries of the left map on conflicts
+def HashMap.union {κ ν : Type} [BEq κ] (s: HashMap κ ν) (t: HashMap κ ν) : HashMap κ ν :=
+  HashMap.unionRaw s t (· == ·)
+
+def HashMap.unionWith {κ ν : Type} [BEq κ] 
+  (s: HashMap κ ν) (t: HashMap κ ν) (f: κ → ν → ν → ν) : HashMap κ ν :=
+  HashMap.unionWithRaw s t (· == ·) f
+
+def HashMap.mergeWith {κ ν : Type} [BEq κ] 
+  (s: HashMap κ ν) (t: @& HashMap κ ν) (both: κ → ν → ν → Option ν) 
+  (onlyLeft: κ → ν → Option ν) (onlyRight: κ → ν → Option ν) : HashMap κ ν :=
+  HashMap.mergeWithRaw s t (· == ·) both onlyLeft onlyRight
+
+instance [BEq κ] : Union (HashMap κ ν) where
+  union := HashMap.union
+
+def HashMap.isSubmapBy {κ ν : Type} [BEq κ] 
+  (s: @& HashMap κ ν) (t: @& HashMap κ ν) (f: ν → ν → Bool) : Bool :=
+  HashMap.isSubmapByRaw s t (· == ·) f
+
+instance [BEq κ] [BEq ν] : BEq (HashMap κ ν) where
+  beq s t := HashMap.eqRaw s t (· == ·) (· == ·)
+
+-- changed entries keep the key of the new map together with the old and the new value
+structure HashMap.Patch (κ : Type) (ν : Type) where
+  added : HashMap κ ν
+  removed : HashMap κ ν
+  changed : HashMap κ (ν × ν)
+
+def HashMap.diff {κ ν : Type} [BEq κ] 
+  (old: @& HashMap κ ν) (new: @& HashMap κ ν) (f: ν → ν → Bool) : HashMap.Patch κ ν :=
+  let (added, removed, changed) := HashMap.diffRaw old new (· == ·) f
+  { added, removed, changed }
+
+def HashMap.applyPatch {κ ν : Type} [BEq κ] (s: HashMap κ ν) (p: @& HashMap.Patch κ ν) : HashMap κ ν :=
+  HashMap.applyPatchRaw s p.added p.removed p.changed (· == ·)
+
+def HashMap.invert {κ ν : Type} [Hashable ν] [BEq ν] (s: @& HashMap κ ν) : HashMap ν (Array κ) :=
+  HashMap.invertRaw s Hashable.hash (· == ·)
+
+def HashMap.compose {α β γ : Type} [Hashable β] [BEq β] 
+  (s: @& HashMap α β) (t: @& HashMap β γ) : HashMap α γ :=
+  HashMap.composeRaw s t Hashable.hash (· == ·)
+
+def HashMap.zipWith {κ ν μ ρ : Type} [BEq κ] 
+  (s: @& HashMap κ ν) (t: @& HashMap κ μ) (f: κ → Option ν → Option μ → Option ρ) : HashMap κ ρ :=
+  HashMap.zipWithRaw s t (· == ·) f
+
+def HashMap.groupBy {α κ : Type} [Hashable κ] [BEq κ] (xs: @& Array α) (key: α → κ) : HashMap κ (Array α) :=
+  HashMap.groupByRaw xs key Hashable.hash (· == ·)
+
+def HashMap.countBy {α κ : Type} [Hashable κ] [BEq κ] (xs: @& Array α) (key: α → κ) : HashMap κ Nat :=
+  HashMap.countByRaw xs key Hashable.hash (· == ·)
+
+abbrev Counter (α : Type) := HashMap α Nat
+
+def Counter.ofArray {α : Type} [Hashable α] [BEq α] (xs: @& Array α) : Counter α :=
+  HashMap.countBy xs id
+
+def Counter.count {α : Type} [Hashable α] [BEq α] (c: @& Counter α) (a: α) : Nat :=
+  (HashMap.getValue? c a).getD 0
+
+-- only the keys are digested, so maps sharing a key set collide whatever their values,
+-- `HashMap.hashWith` takes the values into account at the cost of a walk over the table
+instance : Hashable (HashMap κ ν) where
+  hash s := HashMap.digest s
+
+def HashMap.hashWith {κ ν : Type} [Hashable ν] (s: @& HashMap κ ν) : UInt64 :=
+  HashMap.hashWithRaw s Hashable.hash
+
+def HashMap.toList {κ ν : Type} (s: @& HashMap κ ν) : List (κ × ν) :=
+  (HashMap.toArray s).toList
+
+-- walks the iterator in place, stopping as soon as `f` is done with the loop
+@[specialize] partial def HashMapIter.forIn {κ ν β : Type} {m : Type → Type} [Monad m] 
+  (iter: HashMapIter κ ν) (b: β) (f: κ × ν → β → m (ForInStep β)) : m β :=
+  match iter.next? with
+  | none => pure b
+  | some (k, v, iter) => do
+    match ← f (k, v) b with
+    | .done b => pure b
+    | .yield b => HashMapIter.forIn iter b f
+
+def HashMap.foldM {κ ν β : Type} {m : Type → Type} [Monad m] 
+  (f: β → κ → ν → m β) (init: β) (s: HashMap κ ν) : m β :=
+  HashMapIter.forIn (HashMap.iter s) init fun (k, v) b => ForInStep.yield <$> f b k v
+
+instance {m : Type → Type} : ForIn m (HashMap κ ν) (κ × ν) where
+  forIn s init f := HashMapIter.forIn (HashMap.iter s) init f
+
+instance {m : Type → Type} : ForM m (HashMap κ ν) (κ × ν) where
+  forM s f := HashMapIter.forIn (HashMap.iter s) ⟨⟩ fun kv _ => ForInStep.yield <$> f kv
+
+instance : Stream (HashMapIter κ ν) (κ × ν) where
+  next? iter := match iter.next? with
+    | some (k, v, iter) => some ((k, v), iter)
+    | none => none
+
+instance : ToStream (HashMap κ ν) (HashMapIter κ ν) where
+  toStream := HashMap.iter
+
+instance : Stream (HashMapKeysIter κ ν) κ where
+  next? := HashMapKeysIter.next?
+
+instance : Stream (HashMapValuesIter κ ν) ν where
+  next? := HashMapValuesIter.next?
+
 private partial def formatTail [Repr κ] [Repr ν] (acc: Std.Format) (level: Nat) (tail: HashMapIter κ ν) : Std.Format :=
   match tail.getKey?, tail.getValue? with
   | some k, some v => 
@@ -94,6 +429,7 @@ instance : EmptyCollection (HashMap κ ν) where
 instance : Inhabited (HashMap κ ν) where
   default := HashMap.mk
 
+-- seeds only ever come from `default`, so binary operations reuse the cached hashes of both maps
 structure SeededHashMap (η : Type) (κ : Type) (ν : Type) where
   private mk::
   private map : HashMap κ ν
@@ -109,6 +445,38 @@ def SeededHashMap.insert [HashState.HashState η] [HashState.Hasher η κ] [BEq
       seed := s.seed
     }
 
+def SeededHashMap.insertMany [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (s: SeededHashMap η κ ν) (xs: Array (κ × ν)) : SeededHashMap η κ ν :=
+    let hash := fun k => HashState.HashState.finish <| HashState.Hasher.update s.seed k
+    {
+      map := HashMap.insertManyRaw s.map xs hash (· == ·),
+      seed := s.seed
+    }
+
+def SeededHashMap.ofArray [Inhabited η] [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (xs: Array (κ × ν)) : SeededHashMap η κ ν :=
+    SeededHashMap.insertMany { map := HashMap.mk, seed := default } xs
+
+def SeededHashMap.ofList [Inhabited η] [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (xs: List (κ × ν)) : SeededHashMap η κ ν :=
+    SeededHashMap.ofArray xs.toArray
+
+def SeededHashMap.insert' [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (s: SeededHashMap η κ ν) (k: κ) (v : ν) : SeededHashMap η κ ν × Option ν :=
+    let updated := HashState.Hasher.update s.seed k
+    let hash := HashState.HashState.finish updated
+    let eq := fun k' => k == k'
+    let (map, old) := HashMap.insertRaw' s.map hash k v eq
+    ({ map := map, seed := s.seed }, old)
+
+def SeededHashMap.insertIfNew [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (s: SeededHashMap η κ ν) (k: κ) (v : ν) : SeededHashMap η κ ν × Bool :=
+    let updated := HashState.Hasher.update s.seed k
+    let hash := HashState.HashState.finish updated
+    let eq := fun k' => k == k'
+    let (map, inserted) := HashMap.insertIfNewRaw s.map hash k v eq
+    ({ map := map, seed := s.seed }, inserted)
+
 def SeededHashMap.contains [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
   (s: SeededHashMap η κ ν) (k: κ) : Bool :=
     let updated := HashState.Hasher.update s.seed k
@@ -126,6 +494,14 @@ def SeededHashMap.remove [HashState.HashState η] [HashState.Hasher η κ] [BEq
       seed := s.seed
     }
 
+def SeededHashMap.erase? [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (s: SeededHashMap η κ ν) (k: κ) : SeededHashMap η κ ν × Option (κ × ν) :=
+    let updated := HashState.Hasher.update s.seed k
+    let hash := HashState.HashState.finish updated
+    let eq := fun k' => k == k'
+    let (map, erased) := HashMap.eraseRaw? s.map hash eq
+    ({ map := map, seed := s.seed }, erased)
+
 def SeededHashMap.getValue? [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
   (s: SeededHashMap η κ ν) (k: κ) : Option ν :=
     let updated := HashState.Hasher.update s.seed k
@@ -133,12 +509,200 @@ def SeededHashMap.getValue? [HashState.HashState η] [HashState.Hasher η κ] [B
     let eq := fun k' => k == k'
     HashMap.getValueRaw? s.map hash eq
 
+def SeededHashMap.getKey? [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (s: SeededHashMap η κ ν) (k: κ) : Option κ :=
+    let updated := HashState.Hasher.update s.seed k
+    let hash := HashState.HashState.finish updated
+    let eq := fun k' => k == k'
+    HashMap.getKeyRaw? s.map hash eq
+
+def SeededHashMap.getEntry? [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (s: SeededHashMap η κ ν) (k: κ) : Option (κ × ν) :=
+    let updated := HashState.Hasher.update s.seed k
+    let hash := HashState.HashState.finish updated
+    let eq := fun k' => k == k'
+    HashMap.getEntryRaw? s.map hash eq
+
+def SeededHashMap.alter [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (s: SeededHashMap η κ ν) (k: κ) (f: Option ν → Option ν) : SeededHashMap η κ ν :=
+    let updated := HashState.Hasher.update s.seed k
+    let hash := HashState.HashState.finish updated
+    let eq := fun k' => k == k'
+    {
+      map := HashMap.alterRaw s.map hash k eq f,
+      seed := s.seed
+    }
+
+def SeededHashMap.modify [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (s: SeededHashMap η κ ν) (k: κ) (f: ν → ν) : SeededHashMap η κ ν :=
+    let updated := HashState.Hasher.update s.seed k
+    let hash := HashState.HashState.finish updated
+    let eq := fun k' => k == k'
+    {
+      map := HashMap.modifyRaw s.map hash eq f,
+      seed := s.seed
+    }
+
 def SeededHashMap.len (s: SeededHashMap η κ ν) : USize :=
   HashMap.len s.map
 
 def SeededHashMap.iter (s: SeededHashMap η κ ν) : HashMapIter κ ν :=
   HashMap.iter s.map
 
+def SeededHashMap.withCapacity [Inhabited η] (capacity: USize) : SeededHashMap η κ ν :=
+  { map := HashMap.withCapacity capacity, seed := default }
+
+def SeededHashMap.capacity (s: SeededHashMap η κ ν) : USize :=
+  HashMap.capacity s.map
+
+def SeededHashMap.reserve (s: SeededHashMap η κ ν) (additional: USize) : SeededHashMap η κ ν :=
+  { map := HashMap.reserve s.map additional, seed := s.seed }
+
+def SeededHashMap.shrinkTo (s: SeededHashMap η κ ν) (minSize: USize) : SeededHashMap η κ ν :=
+  { map := HashMap.shrinkTo s.map minSize, seed := s.seed }
+
+def SeededHashMap.shrinkToFit (s: SeededHashMap η κ ν) : SeededHashMap η κ ν :=
+  SeededHashMap.shrinkTo s 0
+
+def SeededHashMap.clear (s: SeededHashMap η κ ν) : SeededHashMap η κ ν :=
+  { map := HashMap.clear s.map, seed := s.seed }
+
+def SeededHashMap.pop? (s: SeededHashMap η κ ν) : SeededHashMap η κ ν × Option (κ × ν) :=
+  let (map, popped) := HashMap.pop? s.map
+  ({ map := map, seed := s.seed }, popped)
+
+def SeededHashMap.sample (s: SeededHashMap η κ ν) (seed: UInt64) : Option (κ × ν) :=
+  HashMap.sample s.map seed
+
+def SeededHashMap.sampleN (s: SeededHashMap η κ ν) (n: USize) (seed: UInt64) : Array (κ × ν) :=
+  HashMap.sampleN s.map n seed
+
+def SeededHashMap.any (s: SeededHashMap η κ ν) (f: κ → ν → Bool) : Bool :=
+  HashMap.any s.map f
+
+def SeededHashMap.all (s: SeededHashMap η κ ν) (f: κ → ν → Bool) : Bool :=
+  HashMap.all s.map f
+
+def SeededHashMap.count (s: SeededHashMap η κ ν) (f: κ → ν → Bool) : USize :=
+  HashMap.count s.map f
+
+def SeededHashMap.find? (s: SeededHashMap η κ ν) (f: κ → ν → Bool) : Option (κ × ν) :=
+  HashMap.find? s.map f
+
+def SeededHashMap.filter (s: SeededHashMap η κ ν) (f: κ → ν → Bool) : SeededHashMap η κ ν :=
+  { map := HashMap.filter s.map f, seed := s.seed }
+
+def SeededHashMap.filterMap (s: SeededHashMap η κ ν) (f: κ → ν → Option μ) : SeededHashMap η κ μ :=
+  { map := HashMap.filterMap s.map f, seed := s.seed }
+
+def SeededHashMap.partition (s: SeededHashMap η κ ν) (f: κ → ν → Bool) 
+  : SeededHashMap η κ ν × SeededHashMap η κ ν :=
+    let (accepted, rejected) := HashMap.partition s.map f
+    ({ map := accepted, seed := s.seed }, { map := rejected, seed := s.seed })
+
+def SeededHashMap.extractIf (s: SeededHashMap η κ ν) (f: κ → ν → Bool) 
+  : SeededHashMap η κ ν × Array (κ × ν) :=
+    let (map, extracted) := HashMap.extractIf s.map f
+    ({ map := map, seed := s.seed }, extracted)
+
+def SeededHashMap.mapValuesWithKey (s: SeededHashMap η κ ν) (f: κ → ν → μ) : SeededHashMap η κ μ :=
+  { map := HashMap.mapValuesWithKey s.map f, seed := s.seed }
+
+def SeededHashMap.mapValues (s: SeededHashMap η κ ν) (f: ν → μ) : SeededHashMap η κ μ :=
+  { map := HashMap.mapValues s.map f, seed := s.seed }
+
+def SeededHashMap.union [BEq κ] (s: SeededHashMap η κ ν) (t: SeededHashMap η κ ν) : SeededHashMap η κ ν :=
+  { map := HashMap.union s.map t.map, seed := s.seed }
+
+def SeededHashMap.unionWith [BEq κ] 
+  (s: SeededHashMap η κ ν) (t: SeededHashMap η κ ν) (f: κ → ν → ν → ν) : SeededHashMap η κ ν :=
+  { map := HashMap.unionWith s.map t.map f, seed := s.seed }
+
+def SeededHashMap.mergeWith [BEq κ] 
+  (s: SeededHashMap η κ ν) (t: SeededHashMap η κ ν) (both: κ → ν → ν → Option ν) 
+  (onlyLeft: κ → ν → Option ν) (onlyRight: κ → ν → Option ν) : SeededHashMap η κ ν :=
+  { map := HashMap.mergeWith s.map t.map both onlyLeft onlyRight, seed := s.seed }
+
+def SeededHashMap.isSubmapBy [BEq κ] 
+  (s: SeededHashMap η κ ν) (t: SeededHashMap η κ ν) (f: ν → ν → Bool) : Bool :=
+  HashMap.isSubmapBy s.map t.map f
+
+instance [BEq κ] [BEq ν] : BEq (SeededHashMap η κ ν) where
+  beq s t := s.map == t.map
+
+def SeededHashMap.diff [BEq κ] 
+  (old: SeededHashMap η κ ν) (new: SeededHashMap η κ ν) (f: ν → ν → Bool) : HashMap.Patch κ ν :=
+  HashMap.diff old.map new.map f
+
+def SeededHashMap.applyPatch [BEq κ] (s: SeededHashMap η κ ν) (p: HashMap.Patch κ ν) : SeededHashMap η κ ν :=
+  { map := HashMap.applyPatch s.map p, seed := s.seed }
+
+def SeededHashMap.invert [HashState.HashState η] [HashState.Hasher η ν] [BEq ν] 
+  (s: SeededHashMap η κ ν) : SeededHashMap η ν (Array κ) :=
+    let hash := fun v => HashState.HashState.finish <| HashState.Hasher.update s.seed v
+    { map := HashMap.invertRaw s.map hash (· == ·), seed := s.seed }
+
+def SeededHashMap.compose [HashState.HashState η] [HashState.Hasher η β] [BEq β] 
+  (s: SeededHashMap η α β) (t: SeededHashMap η β γ) : SeededHashMap η α γ :=
+    let hash := fun b => HashState.HashState.finish <| HashState.Hasher.update t.seed b
+    { map := HashMap.composeRaw s.map t.map hash (· == ·), seed := s.seed }
+
+def SeededHashMap.zipWith [BEq κ] (s: SeededHashMap η κ ν) (t: SeededHashMap η κ μ) 
+  (f: κ → Option ν → Option μ → Option ρ) : SeededHashMap η κ ρ :=
+  { map := HashMap.zipWith s.map t.map f, seed := s.seed }
+
+def SeededHashMap.groupBy [Inhabited η] [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (xs: Array α) (key: α → κ) : SeededHashMap η κ (Array α) :=
+    let seed : η := default
+    let hash := fun k => HashState.HashState.finish <| HashState.Hasher.update seed k
+    { map := HashMap.groupByRaw xs key hash (· == ·), seed := seed }
+
+def SeededHashMap.countBy [Inhabited η] [HashState.HashState η] [HashState.Hasher η κ] [BEq κ] 
+  (xs: Array α) (key: α → κ) : SeededHashMap η κ Nat :=
+    let seed : η := default
+    let hash := fun k => HashState.HashState.finish <| HashState.Hasher.update seed k
+    { map := HashMap.countByRaw xs key hash (· == ·), seed := seed }
+
+instance : Hashable (SeededHashMap η κ ν) where
+  hash s := hash s.map
+
+def SeededHashMap.hashWith [Hashable ν] (s: SeededHashMap η κ ν) : UInt64 :=
+  HashMap.hashWith s.map
+
+def SeededHashMap.toArray (s: SeededHashMap η κ ν) : Array (κ × ν) :=
+  HashMap.toArray s.map
+
+def SeededHashMap.keys (s: SeededHashMap η κ ν) : Array κ :=
+  HashMap.keys s.map
+
+def SeededHashMap.values (s: SeededHashMap η κ ν) : Array ν :=
+  HashMap.values s.map
+
+def SeededHashMap.toList (s: SeededHashMap η κ ν) : List (κ × ν) :=
+  HashMap.toList s.map
+
+def SeededHashMap.fold (f: β → κ → ν → β) (init: β) (s: SeededHashMap η κ ν) : β :=
+  HashMap.fold f init s.map
+
+def SeededHashMap.foldM {m : Type → Type} [Monad m] 
+  (f: β → κ → ν → m β) (init: β) (s: SeededHashMap η κ ν) : m β :=
+  HashMap.foldM f init s.map
+
+instance {m : Type → Type} : ForIn m (SeededHashMap η κ ν) (κ × ν) where
+  forIn s init f := forIn s.map init f
+
+instance {m : Type → Type} : ForM m (SeededHashMap η κ ν) (κ × ν) where
+  forM s f := forM s.map f
+
+def SeededHashMap.keysIter (s: SeededHashMap η κ ν) : HashMapKeysIter κ ν :=
+  HashMap.keysIter s.map
+
+def SeededHashMap.valuesIter (s: SeededHashMap η κ ν) : HashMapValuesIter κ ν :=
+  HashMap.valuesIter s.map
+
+instance : ToStream (SeededHashMap η κ ν) (HashMapIter κ ν) where
+  toStream := SeededHashMap.iter
+
 instance [Inhabited η] : Inhabited (SeededHashMap η κ ν) where
   default := { map := HashMap.mk, seed := default }
 
@@ -16,6 +16,21 @@ instance : Nonempty (HashSetIter α) := (HashSetIterPointed α).property
 @[extern "lean_hashbrown_hashset_create"]
 opaque HashSet.mk : {α : Type} → HashSet α
 
+@[extern "lean_hashbrown_hashset_with_capacity"]
+opaque HashSet.withCapacity : {α : Type} → USize → HashSet α
+
+@[extern "lean_hashbrown_hashset_capacity"]
+opaque HashSet.capacity : {α : Type} → @& HashSet α → USize
+
+@[extern "lean_hashbrown_hashset_reserve"]
+opaque HashSet.reserve : {α : Type} → HashSet α → USize → HashSet α
+
+@[extern "lean_hashbrown_hashset_shrink_to"]
+opaque HashSet.shrinkTo : {α : Type} → HashSet α → USize → HashSet α
+
+@[extern "lean_hashbrown_hashset_clear"]
+opaque HashSet.clear : {α : Type} → HashSet α → HashSet α
+
 @[extern "lean_hashbrown_hashset_insert"]
 private opaque HashSet.insertRaw : {α : Type} 
   → HashSet α → UInt64 → α → @&(α → Bool) → HashSet α 
@@ -24,13 +39,100 @@ private opaque HashSet.insertRaw : {α : Type}
 private opaque HashSet.containsRaw : {α : Type} 
   → @& HashSet α → UInt64 → @&(α → Bool) → Bool
 
+@[extern "lean_hashbrown_hashset_get"]
+private opaque HashSet.getRaw? : {α : Type} 
+  → @& HashSet α → UInt64 → @&(α → Bool) → Option α
+
 @[extern "lean_hashbrown_hashset_remove"]
 private opaque HashSet.removeRaw : {α : Type} 
   → HashSet α → UInt64 → @&(α → Bool) → HashSet α
 
+@[extern "lean_hashbrown_hashset_insert_many"]
+private opaque HashSet.insertManyRaw : {α : Type} 
+  → HashSet α → @& Array α → @&(α → UInt64) → @&(α → α → Bool) → HashSet α
+
+@[extern "lean_hashbrown_hashset_insert_replace"]
+private opaque HashSet.insertRaw' : {α : Type} 
+  → HashSet α → UInt64 → α → @&(α → Bool) → HashSet α × Option α
+
+@[extern "lean_hashbrown_hashset_insert_if_new"]
+private opaque HashSet.insertIfNewRaw : {α : Type} 
+  → HashSet α → UInt64 → α → @&(α → Bool) → HashSet α × Bool
+
+@[extern "lean_hashbrown_hashset_erase"]
+private opaque HashSet.eraseRaw? : {α : Type} 
+  → HashSet α → UInt64 → @&(α → Bool) → HashSet α × Option α
+
+@[extern "lean_hashbrown_hashset_union"]
+private opaque HashSet.unionRaw : {α : Type} 
+  → HashSet α → HashSet α → @&(α → α → Bool) → HashSet α
+
+@[extern "lean_hashbrown_hashset_inter"]
+private opaque HashSet.interRaw : {α : Type} 
+  → HashSet α → @& HashSet α → @&(α → α → Bool) → HashSet α
+
+@[extern "lean_hashbrown_hashset_diff"]
+private opaque HashSet.diffRaw : {α : Type} 
+  → HashSet α → @& HashSet α → @&(α → α → Bool) → HashSet α
+
+@[extern "lean_hashbrown_hashset_symm_diff"]
+private opaque HashSet.symmDiffRaw : {α : Type} 
+  → HashSet α → HashSet α → @&(α → α → Bool) → HashSet α
+
+@[extern "lean_hashbrown_hashset_eq"]
+private opaque HashSet.eqRaw : {α : Type} 
+  → @& HashSet α → @& HashSet α → @&(α → α → Bool) → Bool
+
+@[extern "lean_hashbrown_hashset_is_subset"]
+private opaque HashSet.isSubsetRaw : {α : Type} 
+  → @& HashSet α → @& HashSet α → @&(α → α → Bool) → Bool
+
+@[extern "lean_hashbrown_hashset_is_disjoint"]
+private opaque HashSet.isDisjointRaw : {α : Type} 
+  → @& HashSet α → @& HashSet α → @&(α → α → Bool) → Bool
+
+@[extern "lean_hashbrown_hashset_digest"]
+private opaque HashSet.digest : {α : Type} → @& HashSet α → UInt64
+
 @[extern "lean_hashbrown_hashset_len"]
 opaque HashSet.len : {α : Type} → @& HashSet α → USize    
 
+@[extern "lean_hashbrown_hashset_to_array"]
+opaque HashSet.toArray : {α : Type} → @& HashSet α → Array α
+
+@[extern "lean_hashbrown_hashset_fold"]
+opaque HashSet.fold : {α β : Type} → @&(β → α → β) → β → @& HashSet α → β
+
+@[extern "lean_hashbrown_hashset_pop"]
+opaque HashSet.pop? : {α : Type} → HashSet α → HashSet α × Option α
+
+@[extern "lean_hashbrown_hashset_sample"]
+opaque HashSet.sample : {α : Type} → @& HashSet α → (seed : UInt64) → Option α
+
+@[extern "lean_hashbrown_hashset_sample_n"]
+opaque HashSet.sampleN : {α : Type} → @& HashSet α → USize → (seed : UInt64) → Array α
+
+@[extern "lean_hashbrown_hashset_any"]
+opaque HashSet.any : {α : Type} → @& HashSet α → @&(α → Bool) → Bool
+
+@[extern "lean_hashbrown_hashset_all"]
+opaque HashSet.all : {α : Type} → @& HashSet α → @&(α → Bool) → Bool
+
+@[extern "lean_hashbrown_hashset_count"]
+opaque HashSet.count : {α : Type} → @& HashSet α → @&(α → Bool) → USize
+
+@[extern "lean_hashbrown_hashset_find"]
+opaque HashSet.find? : {α : Type} → @& HashSet α → @&(α → Bool) → Option α
+
+@[extern "lean_hashbrown_hashset_filter"]
+opaque HashSet.filter : {α : Type} → HashSet α → @&(α → Bool) → HashSet α
+
+@[extern "lean_hashbrown_hashset_partition"]
+opaque HashSet.partition : {α : Type} → HashSet α → @&(α → Bool) → HashSet α × HashSet α
+
+@[extern "lean_hashbrown_hashset_extract_if"]
+opaque HashSet.extractIf : {α : Type} → HashSet α → @&(α → Bool) → HashSet α × Array α
+
 @[extern "lean_hashbrown_hashset_get_iter"]
 opaque HashSet.iter : {α : Type} → HashSet α → HashSetIter α 
 
@@ -43,11 +145,43 @@ opaque HashSetIter.get? : {α : Type} → @& HashSetIter α → Option α
 @[extern "lean_hashbrown_hashset_iter_move_next"]
 opaque HashSetIter.next : {α : Type} → HashSetIter α → HashSetIter α
 
+@[extern "lean_hashbrown_hashset_iter_next_element"]
+opaque HashSetIter.next? : {α : Type} → HashSetIter α → Option (α × HashSetIter α)
+
 def HashSet.insert {α : Type} [Hashable α] [BEq α] (s: HashSet α) (a: α) : HashSet α :=
   let hash := Hashable.hash a
   let eq := fun (b: α) => a == b
   HashSet.insertRaw s hash a eq
 
+def HashSet.get? {α : Type} [Hashable α] [BEq α] (s: @& HashSet α) (a: α) : Option α :=
+  let hash := Hashable.hash a
+  let eq := fun (b: α) => a == b
+  HashSet.getRaw? s hash eq
+
+def HashSet.insertMany {α : Type} [Hashable α] [BEq α] (s: HashSet α) (xs: @& Array α) : HashSet α :=
+  HashSet.insertManyRaw s xs Hashable.hash (· == ·)
+
+def HashSet.ofArray {α : Type} [Hashable α] [BEq α] (xs: @& Array α) : HashSet α :=
+  HashSet.insertMany HashSet.mk xs
+
+def HashSet.ofList {α : Type} [Hashable α] [BEq α] (xs: List α) : HashSet α :=
+  HashSet.ofArray xs.toArray
+
+def HashSet.insert' {α : Type} [Hashable α] [BEq α] (s: HashSet α) (a: α) : HashSet α × Option α :=
+  let hash := Hashable.hash a
+  let eq := fun (b: α) => a == b
+  HashSet.insertRaw' s hash a eq
+
+def HashSet.insertIfNew {α : Type} [Hashable α] [BEq α] (s: HashSet α) (a: α) : HashSet α × Bool :=
+  let hash := Hashable.hash a
+  let eq := fun (b: α) => a == b
+  HashSet.insertIfNewRaw s hash a eq
+
+def HashSet.erase? {α : Type} [Hashable α] [BEq α] (s: HashSet α) (a: α) : HashSet α × Option α :=
+  let hash := Hashable.hash a
+  let eq := fun (b: α) => a == b
+  HashSet.eraseRaw? s hash eq
+
 def HashSet.remove {α : Type} [Hashable α] [BEq α] (s: HashSet α) (a: α) : HashSet α :=
   let hash := Hashable.hash a
   let eq := fun (b: α) => a == b
@@ -58,6 +192,72 @@ def HashSet.contains {α : Type} [Hashable α] [BEq α] (s: @& HashSet α) (a: 
   let eq := fun (b: α) => a == b
   HashSet.containsRaw s hash eq
 
+def HashSet.shrinkToFit {α : Type} (s: HashSet α) : HashSet α :=
+  HashSet.shrinkTo s 0
+
+def HashSet.union {α : Type} [BEq α] (s: HashSet α) (t: HashSet α) : HashSet α :=
+  HashSet.unionRaw s t (· == ·)
+
+def HashSet.inter {α : Type} [BEq α] (s: HashSet α) (t: @& HashSet α) : HashSet α :=
+  HashSet.interRaw s t (· == ·)
+
+def HashSet.diff {α : Type} [BEq α] (s: HashSet α) (t: @& HashSet α) : HashSet α :=
+  HashSet.diffRaw s t (· == ·)
+
+def HashSet.symmDiff {α : Type} [BEq α] (s: HashSet α) (t: HashSet α) : HashSet α :=
+  HashSet.symmDiffRaw s t (· == ·)
+
+instance [BEq α] : Union (HashSet α) where
+  union := HashSet.union
+
+instance [BEq α] : Inter (HashSet α) where
+  inter := HashSet.inter
+
+instance [BEq α] : SDiff (HashSet α) where
+  sdiff := HashSet.diff
+
+def HashSet.isSubset {α : Type} [BEq α] (s: @& HashSet α) (t: @& HashSet α) : Bool :=
+  HashSet.isSubsetRaw s t (· == ·)
+
+def HashSet.isDisjoint {α : Type} [BEq α] (s: @& HashSet α) (t: @& HashSet α) : Bool :=
+  HashSet.isDisjointRaw s t (· == ·)
+
+instance [BEq α] : BEq (HashSet α) where
+  beq s t := HashSet.eqRaw s t (· == ·)
+
+-- the digest is maintained on each insertion and removal, hashing a set is O(1)
+instance : Hashable (HashSet α) where
+  hash s := HashSet.digest s
+
+def HashSet.toList {α : Type} (s: @& HashSet α) : List α :=
+  (HashSet.toArray s).toList
+
+-- walks the iterator in place, stopping as soon as `f` is done with the loop
+@[specialize] partial def HashSetIter.forIn {α β : Type} {m : Type → Type} [Monad m] 
+  (iter: HashSetIter α) (b: β) (f: α → β → m (ForInStep β)) : m β :=
+  match iter.next? with
+  | none => pure b
+  | some (a, iter) => do
+    match ← f a b with
+    | .done b => pure b
+    | .yield b => HashSetIter.forIn iter b f
+
+def HashSet.foldM {α β : Type} {m : Type → Type} [Monad m] 
+  (f: β → α → m β) (init: β) (s: HashSet α) : m β :=
+  HashSetIter.forIn (HashSet.iter s) init fun a b => ForInStep.yield <$> f b a
+
+instance {m : Type → Type} : ForIn m (HashSet α) α where
+  forIn s init f := HashSetIter.forIn (HashSet.iter s) init f
+
+instance {m : Type → Type} : ForM m (HashSet α) α where
+  forM s f := HashSetIter.forIn (HashSet.iter s) ⟨⟩ fun a _ => ForInStep.yield <$> f a
+
+instance : Stream (HashSetIter α) α where
+  next? := HashSetIter.next?
+
+instance : ToStream (HashSet α) (HashSetIter α) where
+  toStream := HashSet.iter
+
 private partial def formatTail [Repr α] (acc: Std.Format) (level: Nat) (tail: HashSetIter α) : Std.Format :=
   match tail.get? with
   | some a => 
@@ -83,6 +283,7 @@ instance : EmptyCollection (HashSet α) where
 instance : Inhabited (HashSet α) where
   default := HashSet.mk
 
+-- seeds only ever come from `default`, so binary operations reuse the cached hashes of both sets
 structure SeededHashSet (η : Type) (α : Type) where
   private mk::
   private set : HashSet α
@@ -101,6 +302,38 @@ def SeededHashSet.insert [HashState.HashState η] [HashState.Hasher η α] [BEq
       seed := s.seed
     }
 
+def SeededHashSet.insertMany [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
+  (s: SeededHashSet η α) (xs: Array α) : SeededHashSet η α :=
+    let hash := fun a => HashState.HashState.finish <| HashState.Hasher.update s.seed a
+    {
+      set := HashSet.insertManyRaw s.set xs hash (· == ·),
+      seed := s.seed
+    }
+
+def SeededHashSet.ofArray [Inhabited η] [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
+  (xs: Array α) : SeededHashSet η α :=
+    SeededHashSet.insertMany { set := HashSet.mk, seed := default } xs
+
+def SeededHashSet.ofList [Inhabited η] [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
+  (xs: List α) : SeededHashSet η α :=
+    SeededHashSet.ofArray xs.toArray
+
+def SeededHashSet.insert' [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
+  (s: SeededHashSet η α) (a: α) : SeededHashSet η α × Option α :=
+    let updated := HashState.Hasher.update s.seed a
+    let hash := HashState.HashState.finish updated
+    let eq := fun (b: α) => a == b
+    let (set, old) := HashSet.insertRaw' s.set hash a eq
+    ({ set := set, seed := s.seed }, old)
+
+def SeededHashSet.insertIfNew [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
+  (s: SeededHashSet η α) (a: α) : SeededHashSet η α × Bool :=
+    let updated := HashState.Hasher.update s.seed a
+    let hash := HashState.HashState.finish updated
+    let eq := fun (b: α) => a == b
+    let (set, inserted) := HashSet.insertIfNewRaw s.set hash a eq
+    ({ set := set, seed := s.seed }, inserted)
+
 def SeededHashSet.contains [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
   (s: SeededHashSet η α) (a: α) : Bool :=
     let updated := HashState.Hasher.update s.seed a
@@ -108,6 +341,13 @@ def SeededHashSet.contains [HashState.HashState η] [HashState.Hasher η α] [BE
     let eq := fun (b: α) => a == b
     HashSet.containsRaw s.set hash eq
 
+def SeededHashSet.get? [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
+  (s: SeededHashSet η α) (a: α) : Option α :=
+    let updated := HashState.Hasher.update s.seed a
+    let hash := HashState.HashState.finish updated
+    let eq := fun (b: α) => a == b
+    HashSet.getRaw? s.set hash eq
+
 def SeededHashSet.remove [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
   (s: SeededHashSet η α) (a: α) : SeededHashSet η α :=
     let updated := HashState.Hasher.update s.seed a
@@ -118,12 +358,128 @@ def SeededHashSet.remove [HashState.HashState η] [HashState.Hasher η α] [BEq
       seed := s.seed
     }
 
+def SeededHashSet.erase? [HashState.HashState η] [HashState.Hasher η α] [BEq α] 
+  (s: SeededHashSet η α) (a: α) : SeededHashSet η α × Option α :=
+    let updated := HashState.Hasher.update s.seed a
+    let hash := HashState.HashState.finish updated
+    let eq := fun (b: α) => a == b
+    let (set, erased) := HashSet.eraseRaw? s.set hash eq
+    ({ set := set, seed := s.seed }, erased)
+
 def SeededHashSet.len (s: SeededHashSet η α) : USize :=
   HashSet.len s.set
 
 def SeededHashSet.iter (s: SeededHashSet η α) : HashSetIter α :=
   HashSet.iter s.set
 
+def SeededHashSet.withCapacity [Inhabited η] (capacity: USize) : SeededHashSet η α :=
+  { set := HashSet.withCapacity capacity, seed := default }
+
+def SeededHashSet.capacity (s: SeededHashSet η α) : USize :=
+  HashSet.capacity s.set
+
+def SeededHashSet.reserve (s: SeededHashSet η α) (additional: USize) : SeededHashSet η α :=
+  { set := HashSet.reserve s.set additional, seed := s.seed }
+
+def SeededHashSet.shrinkTo (s: SeededHashSet η α) (minSize: USize) : SeededHashSet η α :=
+  { set := HashSet.shrinkTo s.set minSize, seed := s.seed }
+
+def SeededHashSet.shrinkToFit (s: SeededHashSet η α) : SeededHashSet η α :=
+  SeededHashSet.shrinkTo s 0
+
+def SeededHashSet.clear (s: SeededHashSet η α) : SeededHashSet η α :=
+  { set := HashSet.clear s.set, seed := s.seed }
+
+def SeededHashSet.pop? (s: SeededHashSet η α) : SeededHashSet η α × Option α :=
+  let (set, popped) := HashSet.pop? s.set
+  ({ set := set, seed := s.seed }, popped)
+
+def SeededHashSet.sample (s: SeededHashSet η α) (seed: UInt64) : Option α :=
+  HashSet.sample s.set seed
+
+def SeededHashSet.sampleN (s: SeededHashSet η α) (n: USize) (seed: UInt64) : Array α :=
+  HashSet.sampleN s.set n seed
+
+def SeededHashSet.any (s: SeededHashSet η α) (f: α → Bool) : Bool :=
+  HashSet.any s.set f
+
+def SeededHashSet.all (s: SeededHashSet η α) (f: α → Bool) : Bool :=
+  HashSet.all s.set f
+
+def SeededHashSet.count (s: SeededHashSet η α) (f: α → Bool) : USize :=
+  HashSet.count s.set f
+
+def SeededHashSet.find? (s: SeededHashSet η α) (f: α → Bool) : Option α :=
+  HashSet.find? s.set f
+
+def SeededHashSet.filter (s: SeededHashSet η α) (f: α → Bool) : SeededHashSet η α :=
+  { set := HashSet.filter s.set f, seed := s.seed }
+
+def SeededHashSet.partition (s: SeededHashSet η α) (f: α → Bool) 
+  : SeededHashSet η α × SeededHashSet η α :=
+    let (accepted, rejected) := HashSet.partition s.set f
+    ({ set := accepted, seed := s.seed }, { set := rejected, seed := s.seed })
+
+def SeededHashSet.extractIf (s: SeededHashSet η α) (f: α → Bool) 
+  : SeededHashSet η α × Array α :=
+    let (set, extracted) := HashSet.extractIf s.set f
+    ({ set := set, seed := s.seed }, extracted)
+
+def SeededHashSet.union [BEq α] (s: SeededHashSet η α) (t: SeededHashSet η α) : SeededHashSet η α :=
+  { set := HashSet.union s.set t.set, seed := s.seed }
+
+def SeededHashSet.inter [BEq α] (s: SeededHashSet η α) (t: SeededHashSet η α) : SeededHashSet η α :=
+  { set := HashSet.inter s.set t.set, seed := s.seed }
+
+def SeededHashSet.diff [BEq α] (s: SeededHashSet η α) (t: SeededHashSet η α) : SeededHashSet η α :=
+  { set := HashSet.diff s.set t.set, seed := s.seed }
+
+def SeededHashSet.symmDiff [BEq α] (s: SeededHashSet η α) (t: SeededHashSet η α) : SeededHashSet η α :=
+  { set := HashSet.symmDiff s.set t.set, seed := s.seed }
+
+instance [BEq α] : Union (SeededHashSet η α) where
+  union := SeededHashSet.union
+
+instance [BEq α] : Inter (SeededHashSet η α) where
+  inter := SeededHashSet.inter
+
+instance [BEq α] : SDiff (SeededHashSet η α) where
+  sdiff := SeededHashSet.diff
+
+def SeededHashSet.isSubset [BEq α] (s: SeededHashSet η α) (t: SeededHashSet η α) : Bool :=
+  HashSet.isSubset s.set t.set
+
+def SeededHashSet.isDisjoint [BEq α] (s: SeededHashSet η α) (t: SeededHashSet η α) : Bool :=
+  HashSet.isDisjoint s.set t.set
+
+instance [BEq α] : BEq (SeededHashSet η α) where
+  beq s t := s.set == t.set
+
+instance : Hashable (SeededHashSet η α) where
+  hash s := hash s.set
+
+def SeededHashSet.toArray (s: SeededHashSet η α) : Array α :=
+  HashSet.toArray s.set
+
+def SeededHashSet.toList (s: SeededHashSet η α) : List α :=
+  HashSet.toList s.set
+
+def SeededHashSet.fold (f: β → α → β) (init: β) (s: SeededHashSet η α) : β :=
+  HashSet.fold f init s.set
+
+def SeededHashSet.foldM {m : Type → Type} [Monad m] 
+  (f: β → α → m β) (init: β) (s: SeededHashSet η α) : m β :=
+  HashSet.foldM f init s.set
+
+instance {m : Type → Type} : ForIn m (SeededHashSet η α) α where
+  forIn s init f := forIn s.set init f
+
+instance {m : Type → Type} : ForM m (SeededHashSet η α) α where
+  forM s f := forM s.set f
+
+instance : ToStream (SeededHashSet η α) (HashSetIter α) where
+  toStream := SeededHashSet.iter
+
 instance [Inhabited η] : Inhabited (SeededHashSet η α) where
   default := { set := HashSet.mk, seed := default }
 
@@ -0,0 +1,24 @@
+namespace HashBrown
+
+inductive JoinMode where
+  | inner
+  | leftOuter
+  | semi
+  deriving Repr, BEq, Inhabited
+
+-- rows produced for each left element by the given mode
+def JoinMode.Row (α β : Type) : JoinMode → Type
+  | .inner => α × β
+  | .leftOuter => α × Option β
+  | .semi => α
+
+@[extern "lean_hashbrown_hash_join"]
+private opaque hashJoinRaw : {α β κ : Type} → (mode: JoinMode)
+  → @& Array α → @& Array β → @&(α → κ) → @&(β → κ) → @&(κ → UInt64) → @&(κ → κ → Bool) 
+  → Array (JoinMode.Row α β mode)
+
+-- the smaller array is indexed and the larger one is streamed against it,
+-- rows come in the order of `xs`, matches of one element in the order of `ys`
+def hashJoin {α β κ : Type} [Hashable κ] [BEq κ] (mode: JoinMode) 
+  (xs: @& Array α) (ys: @& Array β) (kx: α → κ) (ky: β → κ) : Array (JoinMode.Row α β mode) :=
+  hashJoinRaw mode xs ys kx ky Hashable.hash (· == ·)
@@ -97,6 +97,22 @@ pub unsafe fn lean_unbox_uint64(obj: lean_obj_res) -> u64 {
     *((*obj).m_objs.as_ptr() as *const u64)
 }
 
+#[inline]
+pub unsafe fn lean_ctor_get(obj: b_lean_obj_arg, i: u32) -> b_lean_obj_res {
+    let obj = obj as *mut lean_ctor_object;
+    *(*obj).m_objs.as_ptr().add(i as usize)
+}
+
+#[inline]
+pub unsafe fn lean_array_size(obj: b_lean_obj_arg) -> usize {
+    (*(obj as *mut lean_array_object)).m_size
+}
+
+#[inline]
+pub unsafe fn lean_array_cptr(obj: b_lean_obj_arg) -> *mut *mut lean_object {
+    (*(obj as *mut lean_array_object)).m_data.as_mut_ptr()
+}
+
 #[inline]
 pub unsafe fn lean_align(size: u32, alignment: u32) -> u32 {
     size / alignment * alignment + alignment * (if size % alignment == 0 { 0 } else { 1 })
@@ -144,6 +160,21 @@ pub unsafe fn lean_alloc_ctor(tag: u32, num_objs: u32, scalar_sz: u32) -> *mut l
     obj
 }
 
+#[inline]
+pub unsafe fn lean_alloc_array(size: usize, capacity: usize) -> lean_obj_res {
+    let obj = lean_alloc_object(
+        core::mem::size_of::<lean_array_object>()
+            + capacity * core::mem::size_of::<*mut lean_object>(),
+    );
+    lean_set_st_header(obj, LeanArray, 0);
+    {
+        let arr = obj as *mut lean_array_object;
+        (*arr).m_size = size;
+        (*arr).m_capacity = capacity;
+    }
+    obj
+}
+
 #[inline]
 pub unsafe fn lean_io_result_mk_ok(obj: lean_obj_arg) -> lean_obj_res {
     let r = lean_alloc_ctor(0, 2, 0);
@@ -196,6 +227,80 @@ pub fn option_to_lean(x: Option<LeanObject>) -> LeanObject {
     }
 }
 
+#[inline]
+pub fn option_from_lean(x: LeanObject) -> Option<LeanObject> {
+    unsafe {
+        if lean_is_scalar(x.0) {
+            None
+        } else {
+            let ctor = x.0 as *mut lean_ctor_object;
+            let inner = (*ctor).m_objs.as_slice(1)[0];
+            lean_inc(inner);
+            Some(LeanObject(inner))
+        }
+    }
+}
+
+#[inline]
+pub fn bool_to_lean(x: bool) -> LeanObject {
+    unsafe { LeanObject(lean_box(x as usize)) }
+}
+
+#[inline]
+pub fn nat_to_lean(x: u64) -> LeanObject {
+    unsafe {
+        if x <= (usize::MAX >> 1) as u64 {
+            LeanObject(lean_box(x as usize))
+        } else {
+            LeanObject(lean_big_uint64_to_nat(x))
+        }
+    }
+}
+
+#[inline]
+pub fn pair_to_lean(fst: LeanObject, snd: LeanObject) -> LeanObject {
+    unsafe {
+        let ctor = lean_alloc_ctor(0, 2, 0);
+        {
+            let ctor = ctor as *mut lean_ctor_object;
+            let objs = (*ctor).m_objs.as_mut_slice(2);
+            objs[0] = fst.into_raw();
+            objs[1] = snd.into_raw();
+        }
+        LeanObject(ctor)
+    }
+}
+
+#[inline]
+pub fn array_to_lean<I: ExactSizeIterator<Item = LeanObject>>(iter: I) -> LeanObject {
+    unsafe {
+        let capacity = iter.len();
+        let arr = lean_alloc_array(0, capacity);
+        let data = lean_array_cptr(arr);
+        let mut size = 0;
+        for x in iter.take(capacity) {
+            *data.add(size) = x.into_raw();
+            size += 1;
+        }
+        (*(arr as *mut lean_array_object)).m_size = size;
+        LeanObject(arr)
+    }
+}
+
+#[inline]
+pub fn uint64_from_lean(x: LeanObject) -> u64 {
+    unsafe { lean_unbox_uint64(x.0) }
+}
+
+#[inline]
+pub fn hash_key(hash_closure: &BorrowedLeanObject, key: &LeanObject) -> u64 {
+    unsafe {
+        let closure = hash_closure.to_owned().into_raw();
+        let boxed = lean_apply_1(closure, key.clone().into_raw());
+        uint64_from_lean(LeanObject::from_raw(boxed))
+    }
+}
+
 #[repr(transparent)]
 pub struct LeanObject(*mut lean_object);
 
@@ -217,11 +322,17 @@ impl Clone for LeanObject {
 }
 
 impl LeanObject {
+    pub unsafe fn from_raw(ptr: *mut lean_object) -> Self {
+        Self(ptr)
+    }
     pub fn into_raw(self) -> *mut lean_object {
         let ptr = self.0;
         core::mem::forget(self);
         ptr
     }
+    pub fn borrow(&self) -> BorrowedLeanObject<'_> {
+        BorrowedLeanObject(self.0, PhantomData)
+    }
 }
 
 #[derive(Clone)]
@@ -267,6 +378,12 @@ pub trait ExternalClass: Sized + 'static {
     };
 }
 
+impl<T> From<Object<T>> for LeanObject {
+    fn from(obj: Object<T>) -> Self {
+        obj.0
+    }
+}
+
 impl<T: ExternalClass> From<T> for Object<T> {
     fn from(t: T) -> Self {
         unsafe {
@@ -278,6 +395,12 @@ impl<T: ExternalClass> From<T> for Object<T> {
     }
 }
 
+impl<T> Object<T> {
+    pub fn is_exclusive(&self) -> bool {
+        unsafe { lean_is_exclusive(self.0 .0) }
+    }
+}
+
 impl<T: Clone + ExternalClass> Object<T> {
     pub fn make_mut(&mut self) -> &mut T {
         unsafe {
@@ -299,6 +422,15 @@ impl<'a> BorrowedLeanObject<'a> {
         }
         LeanObject(self.0)
     }
+    pub unsafe fn field(&self, i: u32) -> BorrowedLeanObject<'a> {
+        BorrowedLeanObject(lean_ctor_get(self.0, i), PhantomData)
+    }
+    pub unsafe fn as_array(&self) -> &'a [BorrowedLeanObject<'a>] {
+        core::slice::from_raw_parts(
+            lean_array_cptr(self.0) as *const BorrowedLeanObject<'a>,
+            lean_array_size(self.0),
+        )
+    }
 }
 
 #[repr(transparent)]
@@ -309,6 +441,10 @@ impl<'a, T> BorrowedObject<'a, T> {
     pub fn to_owned(&self) -> Object<T> {
         Object(self.0.to_owned(), PhantomData)
     }
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.0 .0 == other.0 .0
+    }
 }
 
 impl<T> Deref for BorrowedObject<'_, T> {
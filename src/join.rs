@@ -0,0 +1,162 @@
+use crate::ffi::*;
+use alloc::vec::Vec;
+use hashbrown::raw::RawTable;
+
+const JOIN_INNER: u8 = 0;
+const JOIN_LEFT_OUTER: u8 = 1;
+const JOIN_SEMI: u8 = 2;
+
+struct JoinEntry {
+    hash: u64,
+    key: LeanObject,
+    // positions of the rows sharing the key, in the order of the build array
+    rows: Vec<usize>,
+}
+
+fn extract_key(key_closure: &BorrowedLeanObject, row: &BorrowedLeanObject) -> LeanObject {
+    unsafe {
+        let closure = key_closure.to_owned().into_raw();
+        LeanObject::from_raw(lean_apply_1(closure, row.to_owned().into_raw()))
+    }
+}
+
+fn matches<'a>(
+    eq_closure: &'a BorrowedLeanObject,
+    hash: u64,
+    key: &'a LeanObject,
+) -> impl Fn(&JoinEntry) -> bool + 'a {
+    move |x: &JoinEntry| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_2(closure, key.clone().into_raw(), x.key.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    }
+}
+
+fn build(
+    rows: &[BorrowedLeanObject],
+    key_closure: &BorrowedLeanObject,
+    hash_closure: &BorrowedLeanObject,
+    eq_closure: &BorrowedLeanObject,
+) -> RawTable<JoinEntry> {
+    let hasher = |x: &JoinEntry| x.hash;
+    let mut table = RawTable::with_capacity(rows.len());
+    for (index, row) in rows.iter().enumerate() {
+        let key = extract_key(key_closure, row);
+        let hash = hash_key(hash_closure, &key);
+        match table.find_or_find_insert_slot(hash, matches(eq_closure, hash, &key), hasher) {
+            Ok(occupied) => unsafe { occupied.as_mut().rows.push(index) },
+            Err(empty) => unsafe {
+                let entry = JoinEntry {
+                    hash,
+                    key,
+                    rows: alloc::vec![index],
+                };
+                table.insert_in_slot(hash, empty, entry);
+            },
+        }
+    }
+    table
+}
+
+// emits the rows of one left element given the positions of its matches in the right array
+fn emit(
+    result: &mut Vec<LeanObject>,
+    mode: u8,
+    row: &BorrowedLeanObject,
+    right: &[BorrowedLeanObject],
+    others: &[usize],
+) {
+    match mode {
+        JOIN_INNER => {
+            for &index in others {
+                result.push(pair_to_lean(row.to_owned(), right[index].to_owned()));
+            }
+        }
+        JOIN_LEFT_OUTER if others.is_empty() => {
+            result.push(pair_to_lean(row.to_owned(), option_to_lean(None)));
+        }
+        JOIN_LEFT_OUTER => {
+            for &index in others {
+                let other = option_to_lean(Some(right[index].to_owned()));
+                result.push(pair_to_lean(row.to_owned(), other));
+            }
+        }
+        JOIN_SEMI if !others.is_empty() => result.push(row.to_owned()),
+        _ => {}
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hash_join(
+    mode: u8,
+    left: BorrowedLeanObject,
+    right: BorrowedLeanObject,
+    left_key: BorrowedLeanObject,
+    right_key: BorrowedLeanObject,
+    hash_closure: BorrowedLeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let (left, right) = unsafe { (left.as_array(), right.as_array()) };
+    let mut result = Vec::new();
+    // rows follow the order of the left array, then the order of the right array for each of them
+    if left.len() <= right.len() && mode == JOIN_SEMI {
+        // only whether a left row matched matters, so rows are flagged instead of paired
+        let mut table = build(left, &left_key, &hash_closure, &eq_closure);
+        let mut matched = alloc::vec![false; left.len()];
+        for row in right {
+            let key = extract_key(&right_key, row);
+            let hash = hash_key(&hash_closure, &key);
+            if let Some(entry) = table.get_mut(hash, matches(&eq_closure, hash, &key)) {
+                // rows are flagged once, later matches of the same key find nothing left to do
+                for index in entry.rows.drain(..) {
+                    matched[index] = true;
+                }
+            }
+        }
+        result.extend(
+            left.iter()
+                .zip(matched)
+                .filter(|x| x.1)
+                .map(|x| x.0.to_owned()),
+        );
+    } else if left.len() <= right.len() {
+        let table = build(left, &left_key, &hash_closure, &eq_closure);
+        let mut pairs = Vec::new();
+        for (index, row) in right.iter().enumerate() {
+            let key = extract_key(&right_key, row);
+            let hash = hash_key(&hash_closure, &key);
+            if let Some(entry) = table.get(hash, matches(&eq_closure, hash, &key)) {
+                pairs.extend(entry.rows.iter().map(|&x| (x, index)));
+            }
+        }
+        // the sort is stable, so matches of a left element keep the order of the right array
+        pairs.sort_by_key(|x| x.0);
+        let mut others = Vec::new();
+        let mut pairs = pairs.into_iter().peekable();
+        for (index, row) in left.iter().enumerate() {
+            others.clear();
+            while let Some((_, other)) = pairs.next_if(|x| x.0 == index) {
+                others.push(other);
+            }
+            emit(&mut result, mode, row, right, &others);
+        }
+    } else {
+        let table = build(right, &right_key, &hash_closure, &eq_closure);
+        for row in left {
+            let key = extract_key(&left_key, row);
+            let hash = hash_key(&hash_closure, &key);
+            let entry = table.get(hash, matches(&eq_closure, hash, &key));
+            emit(
+                &mut result,
+                mode,
+                row,
+                right,
+                entry.map_or(&[], |x| &x.rows),
+            );
+        }
+    }
+    array_to_lean(result.into_iter())
+}
@@ -6,7 +6,9 @@ use ffi::{lean_align, LEAN_MAX_SMALL_OBJECT_SIZE, LEAN_OBJECT_SIZE_DELTA};
 extern crate alloc;
 
 mod ffi;
+mod join;
 mod map;
+mod random;
 mod set;
 
 #[cfg(not(test))]
@@ -1,6 +1,8 @@
 use crate::ffi::*;
-use core::ops::{Deref, DerefMut};
-use hashbrown::raw::{RawIter, RawTable};
+use crate::random::{mix64, sample_bucket, sample_buckets, SplitMix64};
+use alloc::vec::Vec;
+use core::ops::Deref;
+use hashbrown::raw::{Bucket, InsertSlot, RawIter, RawTable};
 
 #[derive(Clone)]
 pub struct HashedPair {
@@ -9,19 +11,138 @@ pub struct HashedPair {
     value: LeanObject,
 }
 
+// the table is only reachable mutably through the methods below,
+// so that the digest stays in sync with the content of the table
 #[derive(Clone)]
-pub struct HashMap(RawTable<HashedPair>);
+pub struct HashMap {
+    table: RawTable<HashedPair>,
+    // wrapping sum of the mixed key hashes, values do not take part in it
+    digest: u64,
+    // buckets below this index are known to be empty, so draining the table scans it once
+    scan_start: usize,
+}
 
 impl Deref for HashMap {
     type Target = RawTable<HashedPair>;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.table
+    }
+}
+
+impl From<RawTable<HashedPair>> for HashMap {
+    fn from(table: RawTable<HashedPair>) -> Self {
+        let digest = unsafe { table.iter() }.fold(0u64, |acc, x| {
+            acc.wrapping_add(mix64(unsafe { x.as_ref() }.hash))
+        });
+        Self {
+            table,
+            digest,
+            scan_start: 0,
+        }
     }
 }
 
-impl DerefMut for HashMap {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+impl HashMap {
+    pub fn digest(&self) -> u64 {
+        self.digest
+    }
+    pub fn insert(
+        &mut self,
+        hash: u64,
+        value: HashedPair,
+        hasher: impl Fn(&HashedPair) -> u64,
+    ) -> Bucket<HashedPair> {
+        self.digest = self.digest.wrapping_add(mix64(hash));
+        let rehashed = self.rehashes(1);
+        let bucket = self.table.insert(hash, value, hasher);
+        self.scan_start = if rehashed {
+            0
+        } else {
+            self.scan_start
+                .min(unsafe { self.table.bucket_index(&bucket) })
+        };
+        bucket
+    }
+    pub unsafe fn insert_in_slot(
+        &mut self,
+        hash: u64,
+        slot: InsertSlot,
+        value: HashedPair,
+    ) -> Bucket<HashedPair> {
+        self.digest = self.digest.wrapping_add(mix64(hash));
+        let bucket = self.table.insert_in_slot(hash, slot, value);
+        self.scan_start = self.scan_start.min(self.table.bucket_index(&bucket));
+        bucket
+    }
+    pub unsafe fn erase(&mut self, item: Bucket<HashedPair>) {
+        self.digest = self.digest.wrapping_sub(mix64(item.as_ref().hash));
+        self.table.erase(item)
+    }
+    pub unsafe fn remove(&mut self, item: Bucket<HashedPair>) -> (HashedPair, InsertSlot) {
+        self.digest = self.digest.wrapping_sub(mix64(item.as_ref().hash));
+        self.table.remove(item)
+    }
+    pub fn remove_entry(
+        &mut self,
+        hash: u64,
+        eq: impl FnMut(&HashedPair) -> bool,
+    ) -> Option<HashedPair> {
+        let removed = self.table.remove_entry(hash, eq);
+        if let Some(x) = &removed {
+            self.digest = self.digest.wrapping_sub(mix64(x.hash));
+        }
+        removed
+    }
+    // a rehash, growing or in place, moves entries below the scan start, it can only happen
+    // when the additional items do not fit in what is left of the capacity
+    fn rehashes(&self, additional: usize) -> bool {
+        additional > self.table.capacity() - self.table.len()
+    }
+    pub fn find_or_find_insert_slot(
+        &mut self,
+        hash: u64,
+        eq: impl FnMut(&HashedPair) -> bool,
+        hasher: impl Fn(&HashedPair) -> u64,
+    ) -> Result<Bucket<HashedPair>, InsertSlot> {
+        // a slot found here is only filled by insert_in_slot, which lowers the scan start
+        if self.rehashes(1) {
+            self.scan_start = 0;
+        }
+        self.table.find_or_find_insert_slot(hash, eq, hasher)
+    }
+    pub fn reserve(&mut self, additional: usize, hasher: impl Fn(&HashedPair) -> u64) {
+        if self.rehashes(additional) {
+            self.scan_start = 0;
+        }
+        self.table.reserve(additional, hasher)
+    }
+    pub fn shrink_to(&mut self, min_size: usize, hasher: impl Fn(&HashedPair) -> u64) {
+        self.scan_start = 0;
+        self.table.shrink_to(min_size, hasher)
+    }
+    pub fn get_mut(
+        &mut self,
+        hash: u64,
+        eq: impl FnMut(&HashedPair) -> bool,
+    ) -> Option<&mut HashedPair> {
+        self.table.get_mut(hash, eq)
+    }
+    pub fn pop(&mut self) -> Option<HashedPair> {
+        let index = (self.scan_start..self.table.buckets())
+            .find(|&i| unsafe { self.table.is_bucket_full(i) })?;
+        self.scan_start = index + 1;
+        let bucket = unsafe { self.table.bucket(index) };
+        Some(unsafe { self.remove(bucket) }.0)
+    }
+    pub fn clear(&mut self) {
+        self.digest = 0;
+        self.scan_start = 0;
+        self.table.clear()
+    }
+    fn take(&mut self) -> RawTable<HashedPair> {
+        self.digest = 0;
+        self.scan_start = 0;
+        core::mem::take(&mut self.table)
     }
 }
 
@@ -67,6 +188,86 @@ impl HashMapIter {
     }
 }
 
+#[derive(Clone)]
+pub enum HashMapKeysIter {
+    More {
+        current: LeanObject,
+        next: RawIter<HashedPair>,
+        table: Object<HashMap>,
+    },
+    Finished,
+}
+
+impl HashMapKeysIter {
+    fn new(mut iter: RawIter<HashedPair>, table: Object<HashMap>) -> Self {
+        match iter.next() {
+            Some(current) => Self::More {
+                current: unsafe { current.as_ref().key.clone() },
+                next: iter,
+                table,
+            },
+            None => Self::Finished,
+        }
+    }
+    fn move_next(&mut self) {
+        match self {
+            Self::More {
+                next: iter,
+                current,
+                table: _,
+            } => match iter.next() {
+                Some(next) => {
+                    *current = unsafe { next.as_ref().key.clone() };
+                }
+                None => {
+                    *self = Self::Finished;
+                }
+            },
+            Self::Finished => {}
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum HashMapValuesIter {
+    More {
+        current: LeanObject,
+        next: RawIter<HashedPair>,
+        table: Object<HashMap>,
+    },
+    Finished,
+}
+
+impl HashMapValuesIter {
+    fn new(mut iter: RawIter<HashedPair>, table: Object<HashMap>) -> Self {
+        match iter.next() {
+            Some(current) => Self::More {
+                current: unsafe { current.as_ref().value.clone() },
+                next: iter,
+                table,
+            },
+            None => Self::Finished,
+        }
+    }
+    fn move_next(&mut self) {
+        match self {
+            Self::More {
+                next: iter,
+                current,
+                table: _,
+            } => match iter.next() {
+                Some(next) => {
+                    *current = unsafe { next.as_ref().value.clone() };
+                }
+                None => {
+                    *self = Self::Finished;
+                }
+            },
+            Self::Finished => {}
+        }
+    }
+}
+
 impl ExternalClass for HashMap {
     unsafe fn foreach<F: Fn(LeanObject)>(&self, f: F) {
         for i in self.iter() {
@@ -93,9 +294,177 @@ impl ExternalClass for HashMapIter {
     }
 }
 
+impl ExternalClass for HashMapKeysIter {
+    unsafe fn foreach<F: Fn(LeanObject)>(&self, f: F) {
+        match self {
+            HashMapKeysIter::More {
+                current,
+                table,
+                next: _,
+            } => {
+                f(current.clone());
+                table.foreach(f);
+            }
+            HashMapKeysIter::Finished => {}
+        }
+    }
+}
+
+impl ExternalClass for HashMapValuesIter {
+    unsafe fn foreach<F: Fn(LeanObject)>(&self, f: F) {
+        match self {
+            HashMapValuesIter::More {
+                current,
+                table,
+                next: _,
+            } => {
+                f(current.clone());
+                table.foreach(f);
+            }
+            HashMapValuesIter::Finished => {}
+        }
+    }
+}
+
+fn find_key(
+    table: &RawTable<HashedPair>,
+    hash: u64,
+    key: &LeanObject,
+    eq_closure: &BorrowedLeanObject,
+) -> Option<Bucket<HashedPair>> {
+    let eq = |x: &HashedPair| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_2(closure, key.clone().into_raw(), x.key.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    table.find(hash, eq)
+}
+
+fn apply_pred(closure: &BorrowedLeanObject, pair: &HashedPair) -> bool {
+    unsafe {
+        let closure = closure.to_owned().into_raw();
+        let boxed = lean_apply_2(
+            closure,
+            pair.key.clone().into_raw(),
+            pair.value.clone().into_raw(),
+        );
+        lean_unbox(boxed) != 0
+    }
+}
+
+// entries are moved out when the table is exclusive and cloned otherwise
+fn into_table(mut obj: Object<HashMap>) -> RawTable<HashedPair> {
+    obj.make_mut().take()
+}
+
+fn union_with<F>(
+    left: Object<HashMap>,
+    right: Object<HashMap>,
+    eq_closure: &BorrowedLeanObject,
+    combine: F,
+) -> Object<HashMap>
+where
+    F: Fn(&LeanObject, LeanObject, LeanObject) -> LeanObject,
+{
+    let hasher = |x: &HashedPair| x.hash;
+    let left_is_larger = left.len() >= right.len();
+    let (mut dst, src) = if left_is_larger {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    let map = dst.make_mut();
+    for pair in into_table(src) {
+        match find_key(map, pair.hash, &pair.key, eq_closure) {
+            Some(bucket) => unsafe {
+                let target = bucket.as_mut();
+                let value =
+                    core::mem::replace(&mut target.value, LeanObject::from_raw(lean_box(0)));
+                target.value = if left_is_larger {
+                    combine(&target.key, value, pair.value)
+                } else {
+                    target.key = pair.key;
+                    combine(&target.key, pair.value, value)
+                };
+            },
+            None => {
+                map.insert(pair.hash, pair, hasher);
+            }
+        }
+    }
+    dst
+}
+
+fn is_submap(
+    left: &HashMap,
+    right: &HashMap,
+    eq_closure: &BorrowedLeanObject,
+    value_closure: &BorrowedLeanObject,
+) -> bool {
+    left.len() <= right.len()
+        && unsafe { left.iter() }.all(|bucket| unsafe {
+            let pair = bucket.as_ref();
+            match find_key(right, pair.hash, &pair.key, eq_closure) {
+                Some(other) => {
+                    let closure = value_closure.to_owned().into_raw();
+                    let boxed = lean_apply_2(
+                        closure,
+                        pair.value.clone().into_raw(),
+                        other.as_ref().value.clone().into_raw(),
+                    );
+                    lean_unbox(boxed) != 0
+                }
+                None => false,
+            }
+        })
+}
+
 #[no_mangle]
 pub extern "C" fn lean_hashbrown_hashmap_create() -> Object<HashMap> {
-    HashMap(RawTable::new()).into()
+    HashMap::from(RawTable::new()).into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_with_capacity(capacity: usize) -> Object<HashMap> {
+    HashMap::from(RawTable::with_capacity(capacity)).into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_capacity(obj: BorrowedObject<HashMap>) -> usize {
+    obj.capacity()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_reserve(
+    mut obj: Object<HashMap>,
+    additional: usize,
+) -> Object<HashMap> {
+    let hasher = |x: &HashedPair| x.hash;
+    obj.make_mut().reserve(additional, hasher);
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_shrink_to(
+    mut obj: Object<HashMap>,
+    min_size: usize,
+) -> Object<HashMap> {
+    let hasher = |x: &HashedPair| x.hash;
+    obj.make_mut().shrink_to(min_size, hasher);
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_clear(mut obj: Object<HashMap>) -> Object<HashMap> {
+    // a shared table would be cloned only to be emptied, start afresh instead
+    if !obj.is_exclusive() {
+        return HashMap::from(RawTable::new()).into();
+    }
+    obj.make_mut().clear();
+    obj
 }
 
 #[no_mangle]
@@ -159,71 +528,1000 @@ pub extern "C" fn lean_hashbrown_hashmap_get_value(
 }
 
 #[no_mangle]
-pub extern "C" fn lean_hashbrown_hashmap_len(obj: BorrowedObject<HashMap>) -> usize {
-    obj.len()
+pub extern "C" fn lean_hashbrown_hashmap_get_key(
+    obj: BorrowedObject<HashMap>,
+    hash: u64,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let eq = |x: &HashedPair| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = { lean_apply_1(closure, x.key.clone().into_raw()) };
+                lean_unbox(boxed) != 0
+            }
+    };
+    option_to_lean(
+        obj.find(hash, eq)
+            .map(|x| unsafe { x.as_ref() }.key.clone()),
+    )
 }
 
 #[no_mangle]
-pub extern "C" fn lean_hashbrown_hashmap_insert(
-    mut obj: Object<HashMap>,
+pub extern "C" fn lean_hashbrown_hashmap_get_entry(
+    obj: BorrowedObject<HashMap>,
     hash: u64,
-    key: LeanObject,
-    value: LeanObject,
     eq_closure: BorrowedLeanObject,
-) -> Object<HashMap> {
+) -> LeanObject {
     let eq = |x: &HashedPair| {
         x.hash == hash
             && unsafe {
                 let closure = eq_closure.to_owned().into_raw();
-                let boxed = lean_apply_1(closure, x.key.clone().into_raw());
+                let boxed = { lean_apply_1(closure, x.key.clone().into_raw()) };
                 lean_unbox(boxed) != 0
             }
     };
-    let hasher = |x: &HashedPair| x.hash;
-    let map = obj.make_mut();
-    match map.find_or_find_insert_slot(hash, eq, hasher) {
-        Ok(occupied) => unsafe {
-            *occupied.as_mut() = HashedPair { hash, key, value };
-        },
-        Err(empty) => unsafe {
-            map.insert_in_slot(hash, empty, HashedPair { hash, key, value });
-        },
-    }
-    obj
+    option_to_lean(obj.find(hash, eq).map(|x| {
+        let pair = unsafe { x.as_ref() };
+        pair_to_lean(pair.key.clone(), pair.value.clone())
+    }))
 }
 
 #[no_mangle]
-pub extern "C" fn lean_hashbrown_hashmap_iter_has_kv(iter: BorrowedObject<HashMapIter>) -> u8 {
-    match &*iter {
-        HashMapIter::More { .. } => 1,
-        HashMapIter::Finished => 0,
-    }
+pub extern "C" fn lean_hashbrown_hashmap_len(obj: BorrowedObject<HashMap>) -> usize {
+    obj.len()
 }
 
 #[no_mangle]
-pub extern "C" fn lean_hashbrown_hashmap_iter_get_key(
-    obj: BorrowedObject<HashMapIter>,
-) -> LeanObject {
-    option_to_lean(match &*obj {
-        HashMapIter::More { current, .. } => Some(current.0.clone()),
-        HashMapIter::Finished => None,
+pub extern "C" fn lean_hashbrown_hashmap_digest(obj: BorrowedObject<HashMap>) -> u64 {
+    obj.digest()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_hash_with(
+    obj: BorrowedObject<HashMap>,
+    hash_closure: BorrowedLeanObject,
+) -> u64 {
+    // each key hash is mixed with the hash of its value before the entries are summed
+    unsafe { obj.iter() }.fold(0u64, |acc, x| {
+        let pair = unsafe { x.as_ref() };
+        let value_hash = hash_key(&hash_closure, &pair.value);
+        acc.wrapping_add(mix64(pair.hash ^ mix64(value_hash)))
     })
 }
 
 #[no_mangle]
-pub extern "C" fn lean_hashbrown_hashmap_iter_get_value(
-    obj: BorrowedObject<HashMapIter>,
+pub extern "C" fn lean_hashbrown_hashmap_to_array(obj: BorrowedObject<HashMap>) -> LeanObject {
+    array_to_lean(unsafe { obj.iter() }.map(|x| {
+        let pair = unsafe { x.as_ref() };
+        pair_to_lean(pair.key.clone(), pair.value.clone())
+    }))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_keys(obj: BorrowedObject<HashMap>) -> LeanObject {
+    array_to_lean(unsafe { obj.iter() }.map(|x| unsafe { x.as_ref() }.key.clone()))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_values(obj: BorrowedObject<HashMap>) -> LeanObject {
+    array_to_lean(unsafe { obj.iter() }.map(|x| unsafe { x.as_ref() }.value.clone()))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_fold(
+    fold_closure: BorrowedLeanObject,
+    init: LeanObject,
+    obj: BorrowedObject<HashMap>,
 ) -> LeanObject {
-    option_to_lean(match &*obj {
-        HashMapIter::More { current, .. } => Some(current.1.clone()),
-        HashMapIter::Finished => None,
+    unsafe { obj.iter() }.fold(init, |acc, x| unsafe {
+        let pair = x.as_ref();
+        let closure = fold_closure.to_owned().into_raw();
+        LeanObject::from_raw(lean_apply_3(
+            closure,
+            acc.into_raw(),
+            pair.key.clone().into_raw(),
+            pair.value.clone().into_raw(),
+        ))
     })
 }
 
 #[no_mangle]
-pub extern "C" fn lean_hashbrown_hashmap_iter_move_next(
-    mut obj: Object<HashMapIter>,
-) -> Object<HashMapIter> {
-    obj.make_mut().move_next();
-    obj
+pub extern "C" fn lean_hashbrown_hashmap_any(
+    obj: BorrowedObject<HashMap>,
+    pred_closure: BorrowedLeanObject,
+) -> u8 {
+    unsafe { obj.iter() }.any(|x| apply_pred(&pred_closure, unsafe { x.as_ref() })) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_all(
+    obj: BorrowedObject<HashMap>,
+    pred_closure: BorrowedLeanObject,
+) -> u8 {
+    unsafe { obj.iter() }.all(|x| apply_pred(&pred_closure, unsafe { x.as_ref() })) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_count(
+    obj: BorrowedObject<HashMap>,
+    pred_closure: BorrowedLeanObject,
+) -> usize {
+    unsafe { obj.iter() }
+        .filter(|x| apply_pred(&pred_closure, unsafe { x.as_ref() }))
+        .count()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_find(
+    obj: BorrowedObject<HashMap>,
+    pred_closure: BorrowedLeanObject,
+) -> LeanObject {
+    option_to_lean(
+        unsafe { obj.iter() }
+            .find(|x| apply_pred(&pred_closure, unsafe { x.as_ref() }))
+            .map(|x| {
+                let pair = unsafe { x.as_ref() };
+                pair_to_lean(pair.key.clone(), pair.value.clone())
+            }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_pop(mut obj: Object<HashMap>) -> LeanObject {
+    if obj.is_empty() {
+        return pair_to_lean(obj.into(), option_to_lean(None));
+    }
+    let popped = obj
+        .make_mut()
+        .pop()
+        .map(|pair| pair_to_lean(pair.key, pair.value));
+    pair_to_lean(obj.into(), option_to_lean(popped))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_sample(
+    obj: BorrowedObject<HashMap>,
+    seed: u64,
+) -> LeanObject {
+    let mut rng = SplitMix64::new(seed);
+    option_to_lean(sample_bucket(&obj, &mut rng).map(|x| {
+        let pair = unsafe { x.as_ref() };
+        pair_to_lean(pair.key.clone(), pair.value.clone())
+    }))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_sample_n(
+    obj: BorrowedObject<HashMap>,
+    n: usize,
+    seed: u64,
+) -> LeanObject {
+    let mut rng = SplitMix64::new(seed);
+    array_to_lean(sample_buckets(&obj, n, &mut rng).into_iter().map(|x| {
+        let pair = unsafe { x.as_ref() };
+        pair_to_lean(pair.key.clone(), pair.value.clone())
+    }))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_insert(
+    mut obj: Object<HashMap>,
+    hash: u64,
+    key: LeanObject,
+    value: LeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let eq = |x: &HashedPair| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.key.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    let hasher = |x: &HashedPair| x.hash;
+    let map = obj.make_mut();
+    match map.find_or_find_insert_slot(hash, eq, hasher) {
+        Ok(occupied) => unsafe {
+            *occupied.as_mut() = HashedPair { hash, key, value };
+        },
+        Err(empty) => unsafe {
+            map.insert_in_slot(hash, empty, HashedPair { hash, key, value });
+        },
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_insert_many(
+    mut obj: Object<HashMap>,
+    entries: BorrowedLeanObject,
+    hash_closure: BorrowedLeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let entries = unsafe { entries.as_array() };
+    let hasher = |x: &HashedPair| x.hash;
+    let map = obj.make_mut();
+    // duplicated keys are likely when extending a populated table
+    let additional = if map.is_empty() {
+        entries.len()
+    } else {
+        entries.len().div_ceil(2)
+    };
+    map.reserve(additional, hasher);
+    for entry in entries {
+        let (key, value) = unsafe { (entry.field(0).to_owned(), entry.field(1).to_owned()) };
+        let hash = hash_key(&hash_closure, &key);
+        let eq = |x: &HashedPair| {
+            x.hash == hash
+                && unsafe {
+                    let closure = eq_closure.to_owned().into_raw();
+                    let boxed =
+                        lean_apply_2(closure, key.clone().into_raw(), x.key.clone().into_raw());
+                    lean_unbox(boxed) != 0
+                }
+        };
+        match map.find_or_find_insert_slot(hash, eq, hasher) {
+            Ok(occupied) => unsafe {
+                *occupied.as_mut() = HashedPair { hash, key, value };
+            },
+            Err(empty) => unsafe {
+                map.insert_in_slot(hash, empty, HashedPair { hash, key, value });
+            },
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_insert_replace(
+    mut obj: Object<HashMap>,
+    hash: u64,
+    key: LeanObject,
+    value: LeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let eq = |x: &HashedPair| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.key.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    let hasher = |x: &HashedPair| x.hash;
+    let map = obj.make_mut();
+    let replaced = match map.find_or_find_insert_slot(hash, eq, hasher) {
+        Ok(occupied) => unsafe {
+            let old = core::mem::replace(occupied.as_mut(), HashedPair { hash, key, value });
+            Some(old.value)
+        },
+        Err(empty) => unsafe {
+            map.insert_in_slot(hash, empty, HashedPair { hash, key, value });
+            None
+        },
+    };
+    pair_to_lean(obj.into(), option_to_lean(replaced))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_insert_if_new(
+    mut obj: Object<HashMap>,
+    hash: u64,
+    key: LeanObject,
+    value: LeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let eq = |x: &HashedPair| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.key.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    if obj.find(hash, eq).is_some() {
+        return pair_to_lean(obj.into(), bool_to_lean(false));
+    }
+    let hasher = |x: &HashedPair| x.hash;
+    obj.make_mut()
+        .insert(hash, HashedPair { hash, key, value }, hasher);
+    pair_to_lean(obj.into(), bool_to_lean(true))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_erase(
+    mut obj: Object<HashMap>,
+    hash: u64,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let eq = |x: &HashedPair| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.key.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    let map = obj.make_mut();
+    let erased = map
+        .remove_entry(hash, eq)
+        .map(|pair| pair_to_lean(pair.key, pair.value));
+    pair_to_lean(obj.into(), option_to_lean(erased))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_alter(
+    mut obj: Object<HashMap>,
+    hash: u64,
+    key: LeanObject,
+    eq_closure: BorrowedLeanObject,
+    alter_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let eq = |x: &HashedPair| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.key.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    let hasher = |x: &HashedPair| x.hash;
+    let alter = |x: Option<LeanObject>| unsafe {
+        let closure = alter_closure.to_owned().into_raw();
+        let result = lean_apply_1(closure, option_to_lean(x).into_raw());
+        option_from_lean(LeanObject::from_raw(result))
+    };
+    let map = obj.make_mut();
+    match map.find_or_find_insert_slot(hash, eq, hasher) {
+        Ok(occupied) => unsafe {
+            // move the value out so that the closure sees it uniquely owned
+            let (pair, slot) = map.remove(occupied);
+            if let Some(value) = alter(Some(pair.value)) {
+                map.insert_in_slot(
+                    hash,
+                    slot,
+                    HashedPair {
+                        hash,
+                        key: pair.key,
+                        value,
+                    },
+                );
+            }
+        },
+        Err(empty) => unsafe {
+            if let Some(value) = alter(None) {
+                map.insert_in_slot(hash, empty, HashedPair { hash, key, value });
+            }
+        },
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_modify(
+    mut obj: Object<HashMap>,
+    hash: u64,
+    eq_closure: BorrowedLeanObject,
+    modify_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let eq = |x: &HashedPair| {
+        x.hash == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.key.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    let map = obj.make_mut();
+    if let Some(pair) = map.get_mut(hash, eq) {
+        unsafe {
+            // leave a scalar behind so that the value is passed with refcount 1
+            let value = core::mem::replace(&mut pair.value, LeanObject::from_raw(lean_box(0)));
+            let closure = modify_closure.to_owned().into_raw();
+            pair.value = LeanObject::from_raw(lean_apply_1(closure, value.into_raw()));
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_filter(
+    mut obj: Object<HashMap>,
+    filter_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let map = obj.make_mut();
+    unsafe {
+        for bucket in map.iter() {
+            let pair = bucket.as_ref();
+            let closure = filter_closure.to_owned().into_raw();
+            let boxed = lean_apply_2(
+                closure,
+                pair.key.clone().into_raw(),
+                pair.value.clone().into_raw(),
+            );
+            if lean_unbox(boxed) == 0 {
+                map.erase(bucket);
+            }
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_filter_map(
+    mut obj: Object<HashMap>,
+    filter_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let map = obj.make_mut();
+    unsafe {
+        for bucket in map.iter() {
+            let pair = bucket.as_mut();
+            let value = core::mem::replace(&mut pair.value, LeanObject::from_raw(lean_box(0)));
+            let closure = filter_closure.to_owned().into_raw();
+            let result = lean_apply_2(closure, pair.key.clone().into_raw(), value.into_raw());
+            match option_from_lean(LeanObject::from_raw(result)) {
+                Some(value) => pair.value = value,
+                None => map.erase(bucket),
+            }
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_partition(
+    mut obj: Object<HashMap>,
+    filter_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let hasher = |x: &HashedPair| x.hash;
+    let mut rejected = RawTable::new();
+    let map = obj.make_mut();
+    unsafe {
+        for bucket in map.iter() {
+            let pair = bucket.as_ref();
+            let closure = filter_closure.to_owned().into_raw();
+            let boxed = lean_apply_2(
+                closure,
+                pair.key.clone().into_raw(),
+                pair.value.clone().into_raw(),
+            );
+            if lean_unbox(boxed) == 0 {
+                let (pair, _) = map.remove(bucket);
+                rejected.insert(pair.hash, pair, hasher);
+            }
+        }
+    }
+    let rejected: Object<HashMap> = HashMap::from(rejected).into();
+    pair_to_lean(obj.into(), rejected.into())
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_extract_if(
+    mut obj: Object<HashMap>,
+    filter_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let mut extracted = Vec::new();
+    let map = obj.make_mut();
+    unsafe {
+        for bucket in map.iter() {
+            let pair = bucket.as_ref();
+            let closure = filter_closure.to_owned().into_raw();
+            let boxed = lean_apply_2(
+                closure,
+                pair.key.clone().into_raw(),
+                pair.value.clone().into_raw(),
+            );
+            if lean_unbox(boxed) != 0 {
+                let (pair, _) = map.remove(bucket);
+                extracted.push(pair_to_lean(pair.key, pair.value));
+            }
+        }
+    }
+    pair_to_lean(obj.into(), array_to_lean(extracted.into_iter()))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_map_values(
+    mut obj: Object<HashMap>,
+    map_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    // a shared table is cloned with its control bytes and cached hashes, no key is rehashed
+    let map = obj.make_mut();
+    unsafe {
+        for bucket in map.iter() {
+            let pair = bucket.as_mut();
+            let value = core::mem::replace(&mut pair.value, LeanObject::from_raw(lean_box(0)));
+            let closure = map_closure.to_owned().into_raw();
+            let result = lean_apply_2(closure, pair.key.clone().into_raw(), value.into_raw());
+            pair.value = LeanObject::from_raw(result);
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_union(
+    left: Object<HashMap>,
+    right: Object<HashMap>,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    union_with(left, right, &eq_closure, |_, value, _| value)
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_union_with(
+    left: Object<HashMap>,
+    right: Object<HashMap>,
+    eq_closure: BorrowedLeanObject,
+    combine_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    union_with(left, right, &eq_closure, |key, x, y| unsafe {
+        let closure = combine_closure.to_owned().into_raw();
+        LeanObject::from_raw(lean_apply_3(
+            closure,
+            key.clone().into_raw(),
+            x.into_raw(),
+            y.into_raw(),
+        ))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_merge_with(
+    mut left: Object<HashMap>,
+    right: BorrowedObject<HashMap>,
+    eq_closure: BorrowedLeanObject,
+    both_closure: BorrowedLeanObject,
+    left_closure: BorrowedLeanObject,
+    right_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let hasher = |x: &HashedPair| x.hash;
+    // right-only entries are collected before the left table starts changing
+    let mut pending = Vec::new();
+    unsafe {
+        for bucket in right.iter() {
+            let pair = bucket.as_ref();
+            if find_key(&left, pair.hash, &pair.key, &eq_closure).is_some() {
+                continue;
+            }
+            let closure = right_closure.to_owned().into_raw();
+            let result = lean_apply_2(
+                closure,
+                pair.key.clone().into_raw(),
+                pair.value.clone().into_raw(),
+            );
+            if let Some(value) = option_from_lean(LeanObject::from_raw(result)) {
+                pending.push(HashedPair {
+                    hash: pair.hash,
+                    key: pair.key.clone(),
+                    value,
+                });
+            }
+        }
+    }
+    let map = left.make_mut();
+    unsafe {
+        for bucket in map.iter() {
+            let pair = bucket.as_mut();
+            let value = core::mem::replace(&mut pair.value, LeanObject::from_raw(lean_box(0)));
+            let result = match find_key(&right, pair.hash, &pair.key, &eq_closure) {
+                Some(other) => {
+                    let closure = both_closure.to_owned().into_raw();
+                    lean_apply_3(
+                        closure,
+                        pair.key.clone().into_raw(),
+                        value.into_raw(),
+                        other.as_ref().value.clone().into_raw(),
+                    )
+                }
+                None => {
+                    let closure = left_closure.to_owned().into_raw();
+                    lean_apply_2(closure, pair.key.clone().into_raw(), value.into_raw())
+                }
+            };
+            match option_from_lean(LeanObject::from_raw(result)) {
+                Some(value) => pair.value = value,
+                None => map.erase(bucket),
+            }
+        }
+    }
+    for pair in pending {
+        map.insert(pair.hash, pair, hasher);
+    }
+    left
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_eq(
+    left: BorrowedObject<HashMap>,
+    right: BorrowedObject<HashMap>,
+    eq_closure: BorrowedLeanObject,
+    value_closure: BorrowedLeanObject,
+) -> u8 {
+    (left.ptr_eq(&right)
+        || (left.len() == right.len()
+            && left.digest() == right.digest()
+            && is_submap(&left, &right, &eq_closure, &value_closure))) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_is_submap_by(
+    left: BorrowedObject<HashMap>,
+    right: BorrowedObject<HashMap>,
+    eq_closure: BorrowedLeanObject,
+    value_closure: BorrowedLeanObject,
+) -> u8 {
+    // the value relation is not assumed to be reflexive, so identical tables are still walked
+    is_submap(&left, &right, &eq_closure, &value_closure) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_diff(
+    old: BorrowedObject<HashMap>,
+    new: BorrowedObject<HashMap>,
+    eq_closure: BorrowedLeanObject,
+    value_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let hasher = |x: &HashedPair| x.hash;
+    let mut added = RawTable::new();
+    let mut removed = RawTable::new();
+    let mut changed = RawTable::new();
+    unsafe {
+        for bucket in old.iter() {
+            let pair = bucket.as_ref();
+            match find_key(&new, pair.hash, &pair.key, &eq_closure) {
+                Some(other) => {
+                    let other = other.as_ref();
+                    let closure = value_closure.to_owned().into_raw();
+                    let boxed = lean_apply_2(
+                        closure,
+                        pair.value.clone().into_raw(),
+                        other.value.clone().into_raw(),
+                    );
+                    if lean_unbox(boxed) == 0 {
+                        let value = pair_to_lean(pair.value.clone(), other.value.clone());
+                        let entry = HashedPair {
+                            hash: pair.hash,
+                            key: other.key.clone(),
+                            value,
+                        };
+                        changed.insert(pair.hash, entry, hasher);
+                    }
+                }
+                None => {
+                    removed.insert(pair.hash, pair.clone(), hasher);
+                }
+            }
+        }
+        for bucket in new.iter() {
+            let pair = bucket.as_ref();
+            if find_key(&old, pair.hash, &pair.key, &eq_closure).is_none() {
+                added.insert(pair.hash, pair.clone(), hasher);
+            }
+        }
+    }
+    let added: Object<HashMap> = HashMap::from(added).into();
+    let removed: Object<HashMap> = HashMap::from(removed).into();
+    let changed: Object<HashMap> = HashMap::from(changed).into();
+    pair_to_lean(added.into(), pair_to_lean(removed.into(), changed.into()))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_apply_patch(
+    mut obj: Object<HashMap>,
+    added: BorrowedObject<HashMap>,
+    removed: BorrowedObject<HashMap>,
+    changed: BorrowedObject<HashMap>,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let hasher = |x: &HashedPair| x.hash;
+    let map = obj.make_mut();
+    unsafe {
+        for bucket in removed.iter() {
+            let pair = bucket.as_ref();
+            if let Some(target) = find_key(map, pair.hash, &pair.key, &eq_closure) {
+                map.erase(target);
+            }
+        }
+        map.reserve(added.len(), hasher);
+        // changed entries carry the old and the new value, only the latter is written
+        let entries = added
+            .iter()
+            .map(|x| x.as_ref().clone())
+            .chain(changed.iter().map(|x| {
+                let pair = x.as_ref();
+                HashedPair {
+                    hash: pair.hash,
+                    key: pair.key.clone(),
+                    value: pair.value.borrow().field(1).to_owned(),
+                }
+            }));
+        for pair in entries {
+            match find_key(map, pair.hash, &pair.key, &eq_closure) {
+                Some(target) => *target.as_mut() = pair,
+                None => {
+                    map.insert(pair.hash, pair, hasher);
+                }
+            }
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_iter_has_kv(iter: BorrowedObject<HashMapIter>) -> u8 {
+    match &*iter {
+        HashMapIter::More { .. } => 1,
+        HashMapIter::Finished => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_iter_get_key(
+    obj: BorrowedObject<HashMapIter>,
+) -> LeanObject {
+    option_to_lean(match &*obj {
+        HashMapIter::More { current, .. } => Some(current.0.clone()),
+        HashMapIter::Finished => None,
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_iter_get_value(
+    obj: BorrowedObject<HashMapIter>,
+) -> LeanObject {
+    option_to_lean(match &*obj {
+        HashMapIter::More { current, .. } => Some(current.1.clone()),
+        HashMapIter::Finished => None,
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_iter_move_next(
+    mut obj: Object<HashMapIter>,
+) -> Object<HashMapIter> {
+    obj.make_mut().move_next();
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_iter_next_entry(
+    mut obj: Object<HashMapIter>,
+) -> LeanObject {
+    let (key, value) = match &*obj {
+        HashMapIter::More { current, .. } => current.clone(),
+        HashMapIter::Finished => return option_to_lean(None),
+    };
+    obj.make_mut().move_next();
+    option_to_lean(Some(pair_to_lean(key, pair_to_lean(value, obj.into()))))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_get_keys_iter(
+    obj: Object<HashMap>,
+) -> Object<HashMapKeysIter> {
+    HashMapKeysIter::new(unsafe { obj.iter() }, obj).into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_keys_iter_next(
+    mut obj: Object<HashMapKeysIter>,
+) -> LeanObject {
+    let current = match &*obj {
+        HashMapKeysIter::More { current, .. } => current.clone(),
+        HashMapKeysIter::Finished => return option_to_lean(None),
+    };
+    obj.make_mut().move_next();
+    option_to_lean(Some(pair_to_lean(current, obj.into())))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_get_values_iter(
+    obj: Object<HashMap>,
+) -> Object<HashMapValuesIter> {
+    HashMapValuesIter::new(unsafe { obj.iter() }, obj).into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_values_iter_next(
+    mut obj: Object<HashMapValuesIter>,
+) -> LeanObject {
+    let current = match &*obj {
+        HashMapValuesIter::More { current, .. } => current.clone(),
+        HashMapValuesIter::Finished => return option_to_lean(None),
+    };
+    obj.make_mut().move_next();
+    option_to_lean(Some(pair_to_lean(current, obj.into())))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_invert(
+    obj: BorrowedObject<HashMap>,
+    hash_closure: BorrowedLeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let hasher = |x: &HashedPair| x.hash;
+    let mut inverted = HashMap::from(RawTable::new());
+    for bucket in unsafe { obj.iter() } {
+        let pair = unsafe { bucket.as_ref() };
+        let hash = hash_key(&hash_closure, &pair.value);
+        match find_key(&inverted, hash, &pair.value, &eq_closure) {
+            // the group array is only referenced from the table, so it is pushed in place
+            Some(target) => unsafe {
+                let target = target.as_mut();
+                let keys = core::mem::replace(&mut target.value, LeanObject::from_raw(lean_box(0)));
+                target.value = LeanObject::from_raw(lean_array_push(
+                    keys.into_raw(),
+                    pair.key.clone().into_raw(),
+                ));
+            },
+            None => {
+                let entry = HashedPair {
+                    hash,
+                    key: pair.value.clone(),
+                    value: array_to_lean(core::iter::once(pair.key.clone())),
+                };
+                inverted.insert(hash, entry, hasher);
+            }
+        }
+    }
+    inverted.into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_compose(
+    left: BorrowedObject<HashMap>,
+    right: BorrowedObject<HashMap>,
+    hash_closure: BorrowedLeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let hasher = |x: &HashedPair| x.hash;
+    let mut composed = HashMap::from(RawTable::new());
+    // keys of the left map are unique, so its cached hashes are inserted without probing
+    for bucket in unsafe { left.iter() } {
+        let pair = unsafe { bucket.as_ref() };
+        let hash = hash_key(&hash_closure, &pair.value);
+        if let Some(target) = find_key(&right, hash, &pair.value, &eq_closure) {
+            let entry = HashedPair {
+                hash: pair.hash,
+                key: pair.key.clone(),
+                value: unsafe { target.as_ref() }.value.clone(),
+            };
+            composed.insert(pair.hash, entry, hasher);
+        }
+    }
+    composed.into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_zip_with(
+    left: BorrowedObject<HashMap>,
+    right: BorrowedObject<HashMap>,
+    eq_closure: BorrowedLeanObject,
+    zip_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let hasher = |x: &HashedPair| x.hash;
+    let zip = |key: &LeanObject, lhs: Option<LeanObject>, rhs: Option<LeanObject>| unsafe {
+        let closure = zip_closure.to_owned().into_raw();
+        let result = lean_apply_3(
+            closure,
+            key.clone().into_raw(),
+            option_to_lean(lhs).into_raw(),
+            option_to_lean(rhs).into_raw(),
+        );
+        option_from_lean(LeanObject::from_raw(result))
+    };
+    let mut zipped = HashMap::from(RawTable::with_capacity(left.len().max(right.len())));
+    // keys of both maps are unique, so results are inserted with the cached hashes without probing
+    for bucket in unsafe { left.iter() } {
+        let pair = unsafe { bucket.as_ref() };
+        let other = find_key(&right, pair.hash, &pair.key, &eq_closure)
+            .map(|x| unsafe { x.as_ref() }.value.clone());
+        if let Some(value) = zip(&pair.key, Some(pair.value.clone()), other) {
+            let entry = HashedPair {
+                hash: pair.hash,
+                key: pair.key.clone(),
+                value,
+            };
+            zipped.insert(pair.hash, entry, hasher);
+        }
+    }
+    for bucket in unsafe { right.iter() } {
+        let pair = unsafe { bucket.as_ref() };
+        if find_key(&left, pair.hash, &pair.key, &eq_closure).is_some() {
+            continue;
+        }
+        if let Some(value) = zip(&pair.key, None, Some(pair.value.clone())) {
+            let entry = HashedPair {
+                hash: pair.hash,
+                key: pair.key.clone(),
+                value,
+            };
+            zipped.insert(pair.hash, entry, hasher);
+        }
+    }
+    zipped.into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_group_by(
+    elements: BorrowedLeanObject,
+    key_closure: BorrowedLeanObject,
+    hash_closure: BorrowedLeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    let hasher = |x: &HashedPair| x.hash;
+    let mut groups = HashMap::from(RawTable::new());
+    for element in unsafe { elements.as_array() } {
+        let key = unsafe {
+            let closure = key_closure.to_owned().into_raw();
+            LeanObject::from_raw(lean_apply_1(closure, element.to_owned().into_raw()))
+        };
+        let hash = hash_key(&hash_closure, &key);
+        match find_key(&groups, hash, &key, &eq_closure) {
+            // each group array is only referenced from the table, so it is pushed in place
+            Some(target) => unsafe {
+                let target = target.as_mut();
+                let group =
+                    core::mem::replace(&mut target.value, LeanObject::from_raw(lean_box(0)));
+                target.value = LeanObject::from_raw(lean_array_push(
+                    group.into_raw(),
+                    element.to_owned().into_raw(),
+                ));
+            },
+            None => {
+                let entry = HashedPair {
+                    hash,
+                    key,
+                    value: array_to_lean(core::iter::once(element.to_owned())),
+                };
+                groups.insert(hash, entry, hasher);
+            }
+        }
+    }
+    groups.into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashmap_count_by(
+    elements: BorrowedLeanObject,
+    key_closure: BorrowedLeanObject,
+    hash_closure: BorrowedLeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashMap> {
+    // counters stay unboxed while counting, a Nat is only allocated per distinct key on export
+    let hasher = |x: &(u64, LeanObject, u64)| x.0;
+    let mut counts = RawTable::new();
+    for element in unsafe { elements.as_array() } {
+        let key = unsafe {
+            let closure = key_closure.to_owned().into_raw();
+            LeanObject::from_raw(lean_apply_1(closure, element.to_owned().into_raw()))
+        };
+        let hash = hash_key(&hash_closure, &key);
+        let eq = |x: &(u64, LeanObject, u64)| {
+            x.0 == hash
+                && unsafe {
+                    let closure = eq_closure.to_owned().into_raw();
+                    let boxed =
+                        lean_apply_2(closure, key.clone().into_raw(), x.1.clone().into_raw());
+                    lean_unbox(boxed) != 0
+                }
+        };
+        match counts.find_or_find_insert_slot(hash, eq, hasher) {
+            Ok(occupied) => unsafe { occupied.as_mut().2 += 1 },
+            Err(empty) => unsafe {
+                counts.insert_in_slot(hash, empty, (hash, key, 1));
+            },
+        }
+    }
+    let mut table = RawTable::with_capacity(counts.len());
+    for (hash, key, count) in counts {
+        let value = nat_to_lean(count);
+        unsafe {
+            table.insert_no_grow(hash, HashedPair { hash, key, value });
+        }
+    }
+    HashMap::from(table).into()
 }
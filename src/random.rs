@@ -0,0 +1,80 @@
+use alloc::vec::Vec;
+use hashbrown::raw::{Bucket, RawTable};
+
+// rounds of rejection sampling before falling back to a linear scan
+const REJECTION_ROUNDS: usize = 16;
+
+// finalizer of splitmix64, also used to spread cached hashes before they are combined
+pub fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        mix64(self.0)
+    }
+    pub fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+}
+
+pub fn sample_bucket<T>(table: &RawTable<T>, rng: &mut SplitMix64) -> Option<Bucket<T>> {
+    if table.is_empty() {
+        return None;
+    }
+    for _ in 0..REJECTION_ROUNDS {
+        let index = rng.below(table.buckets());
+        if unsafe { table.is_bucket_full(index) } {
+            return Some(unsafe { table.bucket(index) });
+        }
+    }
+    let index = rng.below(table.len());
+    unsafe { table.iter() }.nth(index)
+}
+
+fn sample_indices<T>(table: &RawTable<T>, n: usize, rng: &mut SplitMix64) -> Vec<Bucket<T>> {
+    let hasher = |x: &usize| mix64(*x as u64);
+    let mut seen = RawTable::with_capacity(n);
+    let mut sample = Vec::with_capacity(n);
+    while sample.len() < n {
+        let index = rng.below(table.buckets());
+        if !unsafe { table.is_bucket_full(index) } {
+            continue;
+        }
+        let hash = hasher(&index);
+        if seen.find(hash, |x| *x == index).is_none() {
+            seen.insert(hash, index, hasher);
+            sample.push(unsafe { table.bucket(index) });
+        }
+    }
+    sample
+}
+
+pub fn sample_buckets<T>(table: &RawTable<T>, n: usize, rng: &mut SplitMix64) -> Vec<Bucket<T>> {
+    // a draw by index costs about buckets / len probes, and repeated draws at most double it
+    // while n stays within half of the table, otherwise walking all len entries is cheaper
+    let len = table.len();
+    if n <= len / 2 && n.saturating_mul(2 * table.buckets()) < len.saturating_mul(len) {
+        return sample_indices(table, n, rng);
+    }
+    let mut reservoir = Vec::with_capacity(n.min(table.len()));
+    for (i, bucket) in unsafe { table.iter() }.enumerate() {
+        if i < n {
+            reservoir.push(bucket);
+        } else {
+            let j = rng.below(i + 1);
+            if j < n {
+                reservoir[j] = bucket;
+            }
+        }
+    }
+    reservoir
+}
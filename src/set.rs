@@ -1,23 +1,136 @@
 use crate::ffi::*;
-use core::ops::{Deref, DerefMut};
-use hashbrown::raw::{RawIter, RawTable};
+use crate::random::{mix64, sample_bucket, sample_buckets, SplitMix64};
+use alloc::vec::Vec;
+use core::ops::Deref;
+use hashbrown::raw::{Bucket, InsertSlot, RawIter, RawTable};
 
 type HashedObject = (u64, LeanObject);
 
+// the table is only reachable mutably through the methods below,
+// so that the digest stays in sync with the content of the table
 #[derive(Clone)]
-#[repr(transparent)]
-pub struct HashSet(RawTable<HashedObject>);
+pub struct HashSet {
+    table: RawTable<HashedObject>,
+    // wrapping sum of the mixed hashes, independent of the order of insertion
+    digest: u64,
+    // buckets below this index are known to be empty, so draining the table scans it once
+    scan_start: usize,
+}
 
 impl Deref for HashSet {
     type Target = RawTable<HashedObject>;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.table
     }
 }
 
-impl DerefMut for HashSet {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+impl From<RawTable<HashedObject>> for HashSet {
+    fn from(table: RawTable<HashedObject>) -> Self {
+        let digest = unsafe { table.iter() }.fold(0u64, |acc, x| {
+            acc.wrapping_add(mix64(unsafe { x.as_ref() }.0))
+        });
+        Self {
+            table,
+            digest,
+            scan_start: 0,
+        }
+    }
+}
+
+impl HashSet {
+    pub fn digest(&self) -> u64 {
+        self.digest
+    }
+    pub fn insert(
+        &mut self,
+        hash: u64,
+        value: HashedObject,
+        hasher: impl Fn(&HashedObject) -> u64,
+    ) -> Bucket<HashedObject> {
+        self.digest = self.digest.wrapping_add(mix64(hash));
+        let rehashed = self.rehashes(1);
+        let bucket = self.table.insert(hash, value, hasher);
+        self.scan_start = if rehashed {
+            0
+        } else {
+            self.scan_start
+                .min(unsafe { self.table.bucket_index(&bucket) })
+        };
+        bucket
+    }
+    pub unsafe fn insert_in_slot(
+        &mut self,
+        hash: u64,
+        slot: InsertSlot,
+        value: HashedObject,
+    ) -> Bucket<HashedObject> {
+        self.digest = self.digest.wrapping_add(mix64(hash));
+        let bucket = self.table.insert_in_slot(hash, slot, value);
+        self.scan_start = self.scan_start.min(self.table.bucket_index(&bucket));
+        bucket
+    }
+    pub unsafe fn erase(&mut self, item: Bucket<HashedObject>) {
+        self.digest = self.digest.wrapping_sub(mix64(item.as_ref().0));
+        self.table.erase(item)
+    }
+    pub unsafe fn remove(&mut self, item: Bucket<HashedObject>) -> (HashedObject, InsertSlot) {
+        self.digest = self.digest.wrapping_sub(mix64(item.as_ref().0));
+        self.table.remove(item)
+    }
+    pub fn remove_entry(
+        &mut self,
+        hash: u64,
+        eq: impl FnMut(&HashedObject) -> bool,
+    ) -> Option<HashedObject> {
+        let removed = self.table.remove_entry(hash, eq);
+        if let Some(x) = &removed {
+            self.digest = self.digest.wrapping_sub(mix64(x.0));
+        }
+        removed
+    }
+    // a rehash, growing or in place, moves entries below the scan start, it can only happen
+    // when the additional items do not fit in what is left of the capacity
+    fn rehashes(&self, additional: usize) -> bool {
+        additional > self.table.capacity() - self.table.len()
+    }
+    pub fn find_or_find_insert_slot(
+        &mut self,
+        hash: u64,
+        eq: impl FnMut(&HashedObject) -> bool,
+        hasher: impl Fn(&HashedObject) -> u64,
+    ) -> Result<Bucket<HashedObject>, InsertSlot> {
+        // a slot found here is only filled by insert_in_slot, which lowers the scan start
+        if self.rehashes(1) {
+            self.scan_start = 0;
+        }
+        self.table.find_or_find_insert_slot(hash, eq, hasher)
+    }
+    pub fn reserve(&mut self, additional: usize, hasher: impl Fn(&HashedObject) -> u64) {
+        if self.rehashes(additional) {
+            self.scan_start = 0;
+        }
+        self.table.reserve(additional, hasher)
+    }
+    pub fn shrink_to(&mut self, min_size: usize, hasher: impl Fn(&HashedObject) -> u64) {
+        self.scan_start = 0;
+        self.table.shrink_to(min_size, hasher)
+    }
+    pub fn pop(&mut self) -> Option<HashedObject> {
+        let index = (self.scan_start..self.table.buckets())
+            .find(|&i| unsafe { self.table.is_bucket_full(i) })?;
+        self.scan_start = index + 1;
+        let bucket = unsafe { self.table.bucket(index) };
+        Some(unsafe { self.remove(bucket) }.0)
+    }
+    pub fn clear(&mut self) {
+        self.digest = 0;
+        self.scan_start = 0;
+        self.table.clear()
+    }
+    fn take(&mut self) -> RawTable<HashedObject> {
+        self.digest = 0;
+        self.scan_start = 0;
+        core::mem::take(&mut self.table)
     }
 }
 
@@ -85,9 +198,98 @@ impl HashSetIter {
     }
 }
 
+fn find_element(
+    table: &RawTable<HashedObject>,
+    hash: u64,
+    target: &LeanObject,
+    eq_closure: &BorrowedLeanObject,
+) -> Option<Bucket<HashedObject>> {
+    let eq = |x: &HashedObject| {
+        x.0 == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed =
+                    lean_apply_2(closure, target.clone().into_raw(), x.1.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    table.find(hash, eq)
+}
+
+fn apply_pred(closure: &BorrowedLeanObject, element: &HashedObject) -> bool {
+    unsafe {
+        let closure = closure.to_owned().into_raw();
+        let boxed = lean_apply_1(closure, element.1.clone().into_raw());
+        lean_unbox(boxed) != 0
+    }
+}
+
+// elements are moved out when the table is exclusive and cloned otherwise
+fn into_table(mut obj: Object<HashSet>) -> RawTable<HashedObject> {
+    obj.make_mut().take()
+}
+
+fn retain<F: Fn(&HashedObject) -> bool>(set: &mut HashSet, keep: F) {
+    unsafe {
+        for bucket in set.iter() {
+            if !keep(bucket.as_ref()) {
+                set.erase(bucket);
+            }
+        }
+    }
+}
+
+fn is_subset(left: &HashSet, right: &HashSet, eq_closure: &BorrowedLeanObject) -> bool {
+    left.len() <= right.len()
+        && unsafe { left.iter() }.all(|bucket| {
+            let element = unsafe { bucket.as_ref() };
+            find_element(right, element.0, &element.1, eq_closure).is_some()
+        })
+}
+
 #[no_mangle]
 extern "C" fn lean_hashbrown_hashset_create() -> Object<HashSet> {
-    HashSet(RawTable::new()).into()
+    HashSet::from(RawTable::new()).into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_with_capacity(capacity: usize) -> Object<HashSet> {
+    HashSet::from(RawTable::with_capacity(capacity)).into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_capacity(obj: BorrowedObject<HashSet>) -> usize {
+    obj.capacity()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_reserve(
+    mut obj: Object<HashSet>,
+    additional: usize,
+) -> Object<HashSet> {
+    let hasher = |x: &HashedObject| x.0;
+    obj.make_mut().reserve(additional, hasher);
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_shrink_to(
+    mut obj: Object<HashSet>,
+    min_size: usize,
+) -> Object<HashSet> {
+    let hasher = |x: &HashedObject| x.0;
+    obj.make_mut().shrink_to(min_size, hasher);
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_clear(mut obj: Object<HashSet>) -> Object<HashSet> {
+    // a shared table would be cloned only to be emptied, start afresh instead
+    if !obj.is_exclusive() {
+        return HashSet::from(RawTable::new()).into();
+    }
+    obj.make_mut().clear();
+    obj
 }
 
 #[no_mangle]
@@ -130,11 +332,124 @@ pub extern "C" fn lean_hashbrown_hashset_contains(
     obj.find(hash, eq).is_some() as u8
 }
 
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_get(
+    obj: BorrowedObject<HashSet>,
+    hash: u64,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let eq = |x: &HashedObject| {
+        x.0 == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = { lean_apply_1(closure, x.1.clone().into_raw()) };
+                lean_unbox(boxed) != 0
+            }
+    };
+    option_to_lean(obj.find(hash, eq).map(|x| unsafe { x.as_ref() }.1.clone()))
+}
+
 #[no_mangle]
 pub extern "C" fn lean_hashbrown_hashset_len(obj: BorrowedObject<HashSet>) -> usize {
     obj.len()
 }
 
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_digest(obj: BorrowedObject<HashSet>) -> u64 {
+    obj.digest()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_to_array(obj: BorrowedObject<HashSet>) -> LeanObject {
+    array_to_lean(unsafe { obj.iter() }.map(|x| unsafe { x.as_ref() }.1.clone()))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_fold(
+    fold_closure: BorrowedLeanObject,
+    init: LeanObject,
+    obj: BorrowedObject<HashSet>,
+) -> LeanObject {
+    unsafe { obj.iter() }.fold(init, |acc, x| unsafe {
+        let closure = fold_closure.to_owned().into_raw();
+        LeanObject::from_raw(lean_apply_2(
+            closure,
+            acc.into_raw(),
+            x.as_ref().1.clone().into_raw(),
+        ))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_any(
+    obj: BorrowedObject<HashSet>,
+    pred_closure: BorrowedLeanObject,
+) -> u8 {
+    unsafe { obj.iter() }.any(|x| apply_pred(&pred_closure, unsafe { x.as_ref() })) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_all(
+    obj: BorrowedObject<HashSet>,
+    pred_closure: BorrowedLeanObject,
+) -> u8 {
+    unsafe { obj.iter() }.all(|x| apply_pred(&pred_closure, unsafe { x.as_ref() })) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_count(
+    obj: BorrowedObject<HashSet>,
+    pred_closure: BorrowedLeanObject,
+) -> usize {
+    unsafe { obj.iter() }
+        .filter(|x| apply_pred(&pred_closure, unsafe { x.as_ref() }))
+        .count()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_find(
+    obj: BorrowedObject<HashSet>,
+    pred_closure: BorrowedLeanObject,
+) -> LeanObject {
+    option_to_lean(
+        unsafe { obj.iter() }
+            .find(|x| apply_pred(&pred_closure, unsafe { x.as_ref() }))
+            .map(|x| unsafe { x.as_ref() }.1.clone()),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_pop(mut obj: Object<HashSet>) -> LeanObject {
+    if obj.is_empty() {
+        return pair_to_lean(obj.into(), option_to_lean(None));
+    }
+    let popped = obj.make_mut().pop().map(|x| x.1);
+    pair_to_lean(obj.into(), option_to_lean(popped))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_sample(
+    obj: BorrowedObject<HashSet>,
+    seed: u64,
+) -> LeanObject {
+    let mut rng = SplitMix64::new(seed);
+    option_to_lean(sample_bucket(&obj, &mut rng).map(|x| unsafe { x.as_ref() }.1.clone()))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_sample_n(
+    obj: BorrowedObject<HashSet>,
+    n: usize,
+    seed: u64,
+) -> LeanObject {
+    let mut rng = SplitMix64::new(seed);
+    array_to_lean(
+        sample_buckets(&obj, n, &mut rng)
+            .into_iter()
+            .map(|x| unsafe { x.as_ref() }.1.clone()),
+    )
+}
+
 #[no_mangle]
 pub extern "C" fn lean_hashbrown_hashset_insert(
     mut obj: Object<HashSet>,
@@ -163,6 +478,324 @@ pub extern "C" fn lean_hashbrown_hashset_insert(
     obj
 }
 
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_insert_many(
+    mut obj: Object<HashSet>,
+    elements: BorrowedLeanObject,
+    hash_closure: BorrowedLeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashSet> {
+    let elements = unsafe { elements.as_array() };
+    let hasher = |x: &HashedObject| x.0;
+    let set = obj.make_mut();
+    // duplicated elements are likely when extending a populated table
+    let additional = if set.is_empty() {
+        elements.len()
+    } else {
+        elements.len().div_ceil(2)
+    };
+    set.reserve(additional, hasher);
+    for element in elements {
+        let target = element.to_owned();
+        let hash = hash_key(&hash_closure, &target);
+        let eq = |x: &HashedObject| {
+            x.0 == hash
+                && unsafe {
+                    let closure = eq_closure.to_owned().into_raw();
+                    let boxed =
+                        lean_apply_2(closure, target.clone().into_raw(), x.1.clone().into_raw());
+                    lean_unbox(boxed) != 0
+                }
+        };
+        match set.find_or_find_insert_slot(hash, eq, hasher) {
+            Ok(occupied) => unsafe {
+                *occupied.as_mut() = (hash, target);
+            },
+            Err(empty) => unsafe {
+                set.insert_in_slot(hash, empty, (hash, target));
+            },
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_insert_replace(
+    mut obj: Object<HashSet>,
+    hash: u64,
+    target: LeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let eq = |x: &HashedObject| {
+        x.0 == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.1.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    let hasher = |x: &HashedObject| x.0;
+    let set = obj.make_mut();
+    let replaced = match set.find_or_find_insert_slot(hash, eq, hasher) {
+        Ok(occupied) => unsafe {
+            let old = core::mem::replace(occupied.as_mut(), (hash, target));
+            Some(old.1)
+        },
+        Err(empty) => unsafe {
+            set.insert_in_slot(hash, empty, (hash, target));
+            None
+        },
+    };
+    pair_to_lean(obj.into(), option_to_lean(replaced))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_insert_if_new(
+    mut obj: Object<HashSet>,
+    hash: u64,
+    target: LeanObject,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let eq = |x: &HashedObject| {
+        x.0 == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.1.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    if obj.find(hash, eq).is_some() {
+        return pair_to_lean(obj.into(), bool_to_lean(false));
+    }
+    let hasher = |x: &HashedObject| x.0;
+    obj.make_mut().insert(hash, (hash, target), hasher);
+    pair_to_lean(obj.into(), bool_to_lean(true))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_erase(
+    mut obj: Object<HashSet>,
+    hash: u64,
+    eq_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let eq = |x: &HashedObject| {
+        x.0 == hash
+            && unsafe {
+                let closure = eq_closure.to_owned().into_raw();
+                let boxed = lean_apply_1(closure, x.1.clone().into_raw());
+                lean_unbox(boxed) != 0
+            }
+    };
+    let set = obj.make_mut();
+    let erased = set.remove_entry(hash, eq).map(|x| x.1);
+    pair_to_lean(obj.into(), option_to_lean(erased))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_filter(
+    mut obj: Object<HashSet>,
+    filter_closure: BorrowedLeanObject,
+) -> Object<HashSet> {
+    let set = obj.make_mut();
+    unsafe {
+        for bucket in set.iter() {
+            let closure = filter_closure.to_owned().into_raw();
+            let boxed = lean_apply_1(closure, bucket.as_ref().1.clone().into_raw());
+            if lean_unbox(boxed) == 0 {
+                set.erase(bucket);
+            }
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_partition(
+    mut obj: Object<HashSet>,
+    filter_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let hasher = |x: &HashedObject| x.0;
+    let mut rejected = RawTable::new();
+    let set = obj.make_mut();
+    unsafe {
+        for bucket in set.iter() {
+            let closure = filter_closure.to_owned().into_raw();
+            let boxed = lean_apply_1(closure, bucket.as_ref().1.clone().into_raw());
+            if lean_unbox(boxed) == 0 {
+                let (element, _) = set.remove(bucket);
+                rejected.insert(element.0, element, hasher);
+            }
+        }
+    }
+    let rejected: Object<HashSet> = HashSet::from(rejected).into();
+    pair_to_lean(obj.into(), rejected.into())
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_extract_if(
+    mut obj: Object<HashSet>,
+    filter_closure: BorrowedLeanObject,
+) -> LeanObject {
+    let mut extracted = Vec::new();
+    let set = obj.make_mut();
+    unsafe {
+        for bucket in set.iter() {
+            let closure = filter_closure.to_owned().into_raw();
+            let boxed = lean_apply_1(closure, bucket.as_ref().1.clone().into_raw());
+            if lean_unbox(boxed) != 0 {
+                let (element, _) = set.remove(bucket);
+                extracted.push(element.1);
+            }
+        }
+    }
+    pair_to_lean(obj.into(), array_to_lean(extracted.into_iter()))
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_union(
+    left: Object<HashSet>,
+    right: Object<HashSet>,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashSet> {
+    let hasher = |x: &HashedObject| x.0;
+    let left_is_larger = left.len() >= right.len();
+    let (mut dst, src) = if left_is_larger {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    let set = dst.make_mut();
+    for element in into_table(src) {
+        match find_element(set, element.0, &element.1, &eq_closure) {
+            Some(bucket) => {
+                // keep the element of the left set
+                if !left_is_larger {
+                    unsafe { *bucket.as_mut() = element };
+                }
+            }
+            None => {
+                set.insert(element.0, element, hasher);
+            }
+        }
+    }
+    dst
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_inter(
+    mut left: Object<HashSet>,
+    right: BorrowedObject<HashSet>,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashSet> {
+    if left.len() <= right.len() {
+        retain(left.make_mut(), |x| {
+            find_element(&right, x.0, &x.1, &eq_closure).is_some()
+        });
+        return left;
+    }
+    let mut table = RawTable::with_capacity(right.len());
+    unsafe {
+        for bucket in right.iter() {
+            let element = bucket.as_ref();
+            if let Some(found) = find_element(&left, element.0, &element.1, &eq_closure) {
+                table.insert_no_grow(element.0, found.as_ref().clone());
+            }
+        }
+    }
+    HashSet::from(table).into()
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_diff(
+    mut left: Object<HashSet>,
+    right: BorrowedObject<HashSet>,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashSet> {
+    let set = left.make_mut();
+    if right.len() < set.len() {
+        unsafe {
+            for bucket in right.iter() {
+                let element = bucket.as_ref();
+                if let Some(found) = find_element(set, element.0, &element.1, &eq_closure) {
+                    set.erase(found);
+                }
+            }
+        }
+    } else {
+        retain(set, |x| {
+            find_element(&right, x.0, &x.1, &eq_closure).is_none()
+        });
+    }
+    left
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_symm_diff(
+    left: Object<HashSet>,
+    right: Object<HashSet>,
+    eq_closure: BorrowedLeanObject,
+) -> Object<HashSet> {
+    let hasher = |x: &HashedObject| x.0;
+    let (mut dst, src) = if left.len() >= right.len() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    let set = dst.make_mut();
+    for element in into_table(src) {
+        match find_element(set, element.0, &element.1, &eq_closure) {
+            Some(bucket) => unsafe {
+                set.erase(bucket);
+            },
+            None => {
+                set.insert(element.0, element, hasher);
+            }
+        }
+    }
+    dst
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_eq(
+    left: BorrowedObject<HashSet>,
+    right: BorrowedObject<HashSet>,
+    eq_closure: BorrowedLeanObject,
+) -> u8 {
+    (left.ptr_eq(&right)
+        || (left.len() == right.len()
+            && left.digest() == right.digest()
+            && is_subset(&left, &right, &eq_closure))) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_is_subset(
+    left: BorrowedObject<HashSet>,
+    right: BorrowedObject<HashSet>,
+    eq_closure: BorrowedLeanObject,
+) -> u8 {
+    (left.ptr_eq(&right) || is_subset(&left, &right, &eq_closure)) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_is_disjoint(
+    left: BorrowedObject<HashSet>,
+    right: BorrowedObject<HashSet>,
+    eq_closure: BorrowedLeanObject,
+) -> u8 {
+    if left.ptr_eq(&right) {
+        return left.is_empty() as u8;
+    }
+    let (smaller, larger) = if left.len() <= right.len() {
+        (&left, &right)
+    } else {
+        (&right, &left)
+    };
+    unsafe { smaller.iter() }.all(|bucket| {
+        let element = unsafe { bucket.as_ref() };
+        find_element(larger, element.0, &element.1, &eq_closure).is_none()
+    }) as u8
+}
+
 #[no_mangle]
 pub extern "C" fn lean_hashbrown_hashset_iter_has_element(obj: BorrowedObject<HashSetIter>) -> u8 {
     match &*obj {
@@ -188,3 +821,15 @@ pub extern "C" fn lean_hashbrown_hashset_iter_move_next(
     obj.make_mut().move_next();
     obj
 }
+
+#[no_mangle]
+pub extern "C" fn lean_hashbrown_hashset_iter_next_element(
+    mut obj: Object<HashSetIter>,
+) -> LeanObject {
+    let element = match &*obj {
+        HashSetIter::More { current, .. } => current.clone(),
+        HashSetIter::Finished => return option_to_lean(None),
+    };
+    obj.make_mut().move_next();
+    option_to_lean(Some(pair_to_lean(element, obj.into())))
+}